@@ -0,0 +1,390 @@
+mod output;
+
+use std::path::{Path, PathBuf};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use demo_core::models::address::Address;
+use demo_core::models::discounts;
+use demo_core::models::partners::Partner;
+use demo_core::models::products::Product;
+use demo_core::models::repository::Repository;
+use demo_core::models::sales::{self, Sale};
+use demo_core::{Error, Result};
+use futures::executor::block_on;
+use output::Format;
+use serde::{de::DeserializeOwned, Serialize};
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Headless companion to the desktop application, for scripts and cron jobs.
+#[derive(Parser)]
+#[command(name = "demo-cli", version)]
+struct Cli {
+    /// Database to work with, the same one the desktop application opens.
+    #[arg(long, global = true, env = "DATABASE_URL", default_value = demo_core::DEFAULT_DATABASE_URL)]
+    database_url: String,
+
+    /// How to print records.
+    #[arg(long, global = true, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage partners.
+    Partners {
+        #[command(subcommand)]
+        command: PartnersCommand,
+    },
+    /// Manage products.
+    Products {
+        #[command(subcommand)]
+        command: ProductsCommand,
+    },
+    /// Manage sales.
+    Sales {
+        #[command(subcommand)]
+        command: SalesCommand,
+    },
+    /// Print the discount of every partner.
+    Discounts,
+    /// Print sales totals.
+    Summary {
+        #[arg(long, value_enum, default_value_t = SummaryBy::Partner)]
+        by: SummaryBy,
+    },
+}
+
+#[derive(Subcommand)]
+enum PartnersCommand {
+    List,
+    Add {
+        #[arg(long = "type")]
+        partner_type: String,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        director: String,
+        #[arg(long)]
+        email: String,
+        #[arg(long)]
+        phone: String,
+        /// "index, region, city, street, building".
+        #[arg(long)]
+        address: String,
+        #[arg(long)]
+        inn: String,
+        #[arg(long, default_value_t = 0)]
+        rating: i64,
+    },
+    Delete {
+        id: String,
+    },
+    #[command(flatten)]
+    Csv(CsvCommand),
+}
+
+#[derive(Subcommand)]
+enum ProductsCommand {
+    List,
+    Add {
+        #[arg(long = "type")]
+        product_type: String,
+        #[arg(long)]
+        name: String,
+        #[arg(long)]
+        article: String,
+        #[arg(long)]
+        minimum_cost: i64,
+    },
+    Delete {
+        id: String,
+    },
+    #[command(flatten)]
+    Csv(CsvCommand),
+}
+
+#[derive(Subcommand)]
+enum SalesCommand {
+    List,
+    Add {
+        #[arg(long)]
+        product: String,
+        #[arg(long)]
+        partner: String,
+        #[arg(long)]
+        quantity: i64,
+        /// Date of the sale, YYYY-MM-DD.
+        #[arg(long, value_parser = parse_date)]
+        date: NaiveDate,
+    },
+    Delete {
+        id: String,
+    },
+    #[command(flatten)]
+    Csv(CsvCommand),
+}
+
+#[derive(Subcommand)]
+enum CsvCommand {
+    /// Add records from a CSV file with the columns of `export`.
+    Import { file: PathBuf },
+    /// Write all records as CSV, to stdout unless a file is given.
+    Export { file: Option<PathBuf> },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum SummaryBy {
+    Partner,
+    Product,
+}
+
+fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = block_on(run(cli)) {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> Result<()> {
+    let db = demo_core::connect(&cli.database_url).await?;
+    execute(&db, cli.format, cli.command).await
+}
+
+async fn execute(db: &SqlitePool, format: Format, command: Command) -> Result<()> {
+    match command {
+        Command::Partners { command } => match command {
+            PartnersCommand::List => output::print(format, &Partner::list(db, &[], None).await?),
+            PartnersCommand::Add {
+                partner_type,
+                name,
+                director,
+                email,
+                phone,
+                address,
+                inn,
+                rating,
+            } => {
+                let partner = Partner::new(
+                    partner_type,
+                    name,
+                    director,
+                    email,
+                    phone,
+                    Address::parse(&address)?,
+                    inn,
+                    rating,
+                );
+                partner.insert(db).await?;
+                output::print(format, &[partner])
+            }
+            PartnersCommand::Delete { id } => {
+                Partner::fetch(db, id).await?.delete(db).await?;
+                Ok(())
+            }
+            PartnersCommand::Csv(CsvCommand::Import { file }) => {
+                import(db, &file, |partner: &mut Partner| {
+                    ensure_id(&mut partner.id);
+                    partner.set_address(Address::parse(&partner.legal_address)?);
+                    Ok(())
+                })
+                .await
+            }
+            PartnersCommand::Csv(CsvCommand::Export { file }) => {
+                write_csv(&Partner::list(db, &[], None).await?, file)
+            }
+        },
+        Command::Products { command } => match command {
+            ProductsCommand::List => output::print(format, &Product::list(db, &[], None).await?),
+            ProductsCommand::Add {
+                product_type,
+                name,
+                article,
+                minimum_cost,
+            } => {
+                let product = Product::new(product_type, name, article, minimum_cost);
+                product.insert(db).await?;
+                output::print(format, &[product])
+            }
+            ProductsCommand::Delete { id } => {
+                Product::fetch(db, id).await?.delete(db).await?;
+                Ok(())
+            }
+            ProductsCommand::Csv(CsvCommand::Import { file }) => {
+                import(db, &file, |product: &mut Product| {
+                    ensure_id(&mut product.id);
+                    Ok(())
+                })
+                .await
+            }
+            ProductsCommand::Csv(CsvCommand::Export { file }) => {
+                write_csv(&Product::list(db, &[], None).await?, file)
+            }
+        },
+        Command::Sales { command } => match command {
+            SalesCommand::List => output::print(format, &Sale::list(db, &[], None).await?),
+            SalesCommand::Add {
+                product,
+                partner,
+                quantity,
+                date,
+            } => {
+                let sale = Sale::new(product, quantity, date, partner);
+                sale.insert(db).await?;
+                output::print(format, &[sale])
+            }
+            SalesCommand::Delete { id } => {
+                Sale::fetch(db, id).await?.delete(db).await?;
+                Ok(())
+            }
+            SalesCommand::Csv(CsvCommand::Import { file }) => {
+                import(db, &file, |sale: &mut Sale| {
+                    ensure_id(&mut sale.id);
+                    Ok(())
+                })
+                .await
+            }
+            SalesCommand::Csv(CsvCommand::Export { file }) => {
+                write_csv(&Sale::list(db, &[], None).await?, file)
+            }
+        },
+        Command::Discounts => output::print(format, &discounts::get_all(db).await?),
+        Command::Summary { by } => {
+            let summary = match by {
+                SummaryBy::Partner => sales::summary_by_partner(db).await?,
+                SummaryBy::Product => sales::summary_by_product(db).await?,
+            };
+            output::print(format, &summary)
+        }
+    }
+}
+
+/// Adds every record of the file, completed by `prepare`, in one transaction:
+/// a row that fails leaves the table as it was rather than with the rows before it.
+async fn import<T: Repository + DeserializeOwned>(
+    db: &SqlitePool,
+    file: &Path,
+    prepare: impl Fn(&mut T) -> Result<()>,
+) -> Result<()> {
+    let records = read_csv::<T>(file)?;
+
+    let mut transaction = db.begin().await?;
+    for mut record in records {
+        prepare(&mut record)?;
+        record.insert_in(&mut transaction).await?;
+    }
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+fn ensure_id(id: &mut String) {
+    if id.is_empty() {
+        *id = Uuid::new_v4().to_string();
+    }
+}
+
+fn read_csv<T: DeserializeOwned>(file: &Path) -> Result<Vec<T>> {
+    csv::Reader::from_path(file)
+        .map_err(|e| Error::Any(e.to_string()))?
+        .deserialize()
+        .enumerate()
+        .map(|(i, record)| {
+            // Row 1 is the header
+            record.map_err(|e| Error::Any(format!("{}, строка {}: {}", file.display(), i + 2, e)))
+        })
+        .collect()
+}
+
+fn write_csv<T: Serialize>(rows: &[T], file: Option<PathBuf>) -> Result<()> {
+    let writer: Box<dyn std::io::Write> = match file {
+        Some(file) => Box::new(std::fs::File::create(file).map_err(|e| Error::Any(e.to_string()))?),
+        None => Box::new(std::io::stdout()),
+    };
+
+    let mut writer = csv::Writer::from_writer(writer);
+    for row in rows {
+        writer.serialize(row).map_err(|e| Error::Any(e.to_string()))?;
+    }
+    writer.flush().map_err(|e| Error::Any(e.to_string()))
+}
+
+fn parse_date(date: &str) -> std::result::Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use demo_core::models::product_types::ProductType;
+    use demo_core::test_util::connect_in_memory;
+
+    use super::*;
+
+    const PARTNERS_HEADER: &str = "partner_type,partner_name,director,email,phone,legal_address,inn,rating\n";
+    const PARTNER: &str = "ООО,Стройсервис,Соловьев Андрей Николаевич,ansolovev@st.ru,812 223 32 00,\
+        \"188910, Ленинградская область, город Приморск, ул. Парковая, 21\",4440391029,7\n";
+
+    /// A CSV file of its own in the temporary directory.
+    fn csv_file(contents: &str) -> PathBuf {
+        let file = std::env::temp_dir().join(format!("demo-cli-{}.csv", Uuid::new_v4()));
+        std::fs::write(&file, contents).unwrap();
+        file
+    }
+
+    fn import(db: &SqlitePool, table: &str, contents: &str) -> Result<()> {
+        let file = csv_file(contents);
+        let cli = Cli::parse_from(["demo-cli", table, "import", file.to_str().unwrap()]);
+        let result = block_on(execute(db, cli.format, cli.command));
+        std::fs::remove_file(file).unwrap();
+        result
+    }
+
+    #[test]
+    fn partner_import_splits_the_addresses() {
+        let db = block_on(connect_in_memory()).unwrap();
+
+        import(&db, "partners", &[PARTNERS_HEADER, PARTNER].concat()).unwrap();
+
+        let partners = block_on(Partner::list(&db, &[], None)).unwrap();
+        assert_eq!(partners.len(), 1);
+        assert_eq!(
+            (partners[0].postal_index.as_str(), partners[0].city.as_str(), partners[0].street.as_str()),
+            ("188910", "Приморск", "ул. Парковая")
+        );
+    }
+
+    #[test]
+    fn failed_imports_add_nothing() {
+        let db = block_on(connect_in_memory()).unwrap();
+
+        let unparsed = "ООО,Паркет 29,Петров Василий Петрович,vppetrov@vl.ru,987 123 56 78,\
+            \"Архангельская область, город Северодвинск\",3333888520,7\n";
+        assert!(matches!(
+            import(&db, "partners", &[PARTNERS_HEADER, PARTNER, unparsed].concat()),
+            Err(Error::Validation { column, .. }) if column == "legal_address"
+        ));
+        assert_eq!(block_on(Partner::count(&db, &[])).unwrap(), 0);
+
+        import(&db, "partners", &[PARTNERS_HEADER, PARTNER].concat()).unwrap();
+        let partner = block_on(Partner::list(&db, &[], None)).unwrap().remove(0);
+        block_on(ProductType::new("Ламинат".into(), 2.35).insert(&db)).unwrap();
+        let product = Product::new("Ламинат".into(), "Ламинат Дуб 12 мм".into(), "7750282".into(), 1799);
+        block_on(product.insert(&db)).unwrap();
+
+        let sales = format!(
+            "product_id,quantity,sale_date,partner_id\n{0},300,2024-01-10,{1}\nproduct-0,300,2024-01-11,{1}\n",
+            product.id, partner.id
+        );
+        assert!(matches!(
+            import(&db, "sales", &sales),
+            Err(Error::ForeignKeyViolation { column: Some(column), .. }) if column == "product_id"
+        ));
+        assert_eq!(block_on(Sale::count(&db, &[])).unwrap(), 0);
+    }
+}
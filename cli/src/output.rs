@@ -0,0 +1,138 @@
+use clap::ValueEnum;
+use demo_core::models::discounts::PartnerDiscount;
+use demo_core::models::partners::Partner;
+use demo_core::models::products::Product;
+use demo_core::models::sales::{Sale, SalesSummary};
+use serde::Serialize;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Format {
+    Table,
+    Json,
+}
+
+/// A record that can be printed as a table row.
+pub trait Row {
+    fn headers() -> Vec<&'static str>;
+    fn cells(&self) -> Vec<String>;
+}
+
+pub fn print<T: Row + Serialize>(format: Format, rows: &[T]) -> demo_core::Result<()> {
+    match format {
+        Format::Json => {
+            let json = serde_json::to_string_pretty(rows)
+                .map_err(|e| demo_core::Error::Any(e.to_string()))?;
+            println!("{}", json);
+        }
+        Format::Table => print_table(T::headers(), rows.iter().map(Row::cells).collect()),
+    }
+    Ok(())
+}
+
+fn print_table(headers: Vec<&str>, rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.chars().count()).collect();
+    rows.iter().for_each(|row| {
+        row.iter().enumerate().for_each(|(i, cell)| {
+            widths[i] = widths[i].max(cell.chars().count());
+        })
+    });
+
+    let line = |cells: Vec<&str>| {
+        cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{}{}", cell, " ".repeat(width - cell.chars().count())))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    };
+
+    println!("{}", line(headers));
+    println!(
+        "{}",
+        widths.iter().map(|width| "-".repeat(*width)).collect::<Vec<_>>().join("-+-")
+    );
+    rows.iter()
+        .for_each(|row| println!("{}", line(row.iter().map(String::as_str).collect())));
+}
+
+impl Row for Partner {
+    fn headers() -> Vec<&'static str> {
+        vec!["ID", "Тип", "Название", "Директор", "E-Mail", "Телефон", "ИНН", "Рейтинг"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.partner_type.clone(),
+            self.partner_name.clone(),
+            self.director.clone(),
+            self.email.clone(),
+            self.phone.clone(),
+            self.inn.clone(),
+            self.rating.to_string(),
+        ]
+    }
+}
+
+impl Row for Product {
+    fn headers() -> Vec<&'static str> {
+        vec!["ID", "Тип", "Название", "Артикул", "Минимальная цена"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.product_type.clone(),
+            self.product_name.clone(),
+            self.article_number.clone(),
+            self.minimum_cost.to_string(),
+        ]
+    }
+}
+
+impl Row for Sale {
+    fn headers() -> Vec<&'static str> {
+        vec!["ID", "Продукт", "Партнер", "Количество", "Дата"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.product_id.clone(),
+            self.partner_id.clone(),
+            self.quantity.to_string(),
+            self.sale_date.to_string(),
+        ]
+    }
+}
+
+impl Row for PartnerDiscount {
+    fn headers() -> Vec<&'static str> {
+        vec!["ID", "Партнер", "Продано", "Скидка, %"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.partner_id.clone(),
+            self.partner_name.clone(),
+            self.total_quantity.to_string(),
+            self.discount.to_string(),
+        ]
+    }
+}
+
+impl Row for SalesSummary {
+    fn headers() -> Vec<&'static str> {
+        vec!["ID", "Название", "Продаж", "Количество", "Сумма"]
+    }
+
+    fn cells(&self) -> Vec<String> {
+        vec![
+            self.id.clone(),
+            self.name.clone(),
+            self.sales.to_string(),
+            self.quantity.to_string(),
+            self.amount.to_string(),
+        ]
+    }
+}
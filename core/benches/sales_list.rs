@@ -0,0 +1,132 @@
+//! Queries one frame of the Sales list runs on 10 000 sales: fetching the
+//! product and partner of every shown sale, as the list used to, against
+//! joining them with `sales::get_all_detailed`. Then the partners, products
+//! and product types of the form and filters, listed every frame as the view
+//! used to, against reading the versions of their tables to keep them cached.
+//!
+//! `cargo bench -p demo-core --bench sales_list`
+
+use std::time::{Duration, Instant};
+
+use demo_core::models::partners::Partner;
+use demo_core::models::product_types::ProductType;
+use demo_core::models::products::Product;
+use demo_core::models::repository::Repository;
+use demo_core::models::sales::{self, Sale};
+use futures::executor::block_on;
+use sqlx::SqlitePool;
+
+const SALES: i64 = 10_000;
+const PARTNERS: i64 = 200;
+const PRODUCTS: i64 = 50;
+/// Frames measured for each case; the time shown is their average.
+const FRAMES: u32 = 5;
+
+/// A database of `SALES` sales over ten years, spread over the partners and products.
+async fn dataset() -> demo_core::Result<SqlitePool> {
+    let db = demo_core::test_util::connect_in_memory().await?;
+
+    sqlx::query("INSERT INTO product_types (product_type, coefficient) VALUES ('Ламинат', 2.35)")
+        .execute(&db)
+        .await?;
+    sqlx::query(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < $1)
+        INSERT INTO partners (partner_type, partner_name, director, email, phone, legal_address, inn, rating, id)
+        SELECT 'ООО', 'Партнер ' || i, 'Иванов Иван Иванович', 'partner' || i || '@example.com',
+            '+7 900 000 00 00', '652050, Кемеровская область, город Юрга, ул. Лесная, 15', 1000000000 + i, 5,
+            'partner-' || i
+        FROM n",
+    )
+    .bind(PARTNERS)
+    .execute(&db)
+    .await?;
+    sqlx::query(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < $1)
+        INSERT INTO products (product_type, product_name, article_number, minimum_cost, id)
+        SELECT 'Ламинат', 'Продукт ' || i, 8000000 + i, 1000 + i, 'product-' || i
+        FROM n",
+    )
+    .bind(PRODUCTS)
+    .execute(&db)
+    .await?;
+    sqlx::query(
+        "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < $1)
+        INSERT INTO sales (product_id, quantity, sale_date, partner_id, id)
+        SELECT 'product-' || (1 + i % $2), 1 + i % 500, date('2015-01-01', '+' || (i % 3650) || ' days'),
+            'partner-' || (1 + i % $3), 'sale-' || i
+        FROM n",
+    )
+    .bind(SALES)
+    .bind(PRODUCTS)
+    .bind(PARTNERS)
+    .execute(&db)
+    .await?;
+
+    Ok(db)
+}
+
+/// A frame of the list before: the sales, then the product and the partner of each.
+async fn fetch_each(db: &SqlitePool, rows: i64) -> demo_core::Result<usize> {
+    let sales = Sale::page(db, &[], None, rows).await?;
+    let mut queries = 1;
+    for sale in &sales {
+        Product::fetch(db, sale.product_id.as_str()).await?;
+        Partner::fetch(db, sale.partner_id.as_str()).await?;
+        queries += 2;
+    }
+    Ok(queries)
+}
+
+/// A frame of the list now: one query joining the names in.
+async fn joined(db: &SqlitePool, rows: i64) -> demo_core::Result<usize> {
+    sales::get_all_detailed(db, &[], None, rows).await?;
+    Ok(1)
+}
+
+/// The choices of a frame before: the three tables listed whole.
+async fn list_choices(db: &SqlitePool) -> demo_core::Result<usize> {
+    Partner::list(db, &[], None).await?;
+    Product::list(db, &[], None).await?;
+    ProductType::list(db, &[], None).await?;
+    Ok(3)
+}
+
+/// The choices of a frame now: the versions showing the cached lists are current.
+async fn cached_choices(db: &SqlitePool) -> demo_core::Result<usize> {
+    Partner::version(db).await?;
+    Product::version(db).await?;
+    ProductType::version(db).await?;
+    Ok(3)
+}
+
+/// Queries of a frame and the average time of `FRAMES` frames.
+fn measure(frame: impl Fn() -> demo_core::Result<usize>) -> demo_core::Result<(usize, Duration)> {
+    let start = Instant::now();
+    let mut queries = 0;
+    for _ in 0..FRAMES {
+        queries = frame()?;
+    }
+    Ok((queries, start.elapsed() / FRAMES))
+}
+
+fn main() -> demo_core::Result<()> {
+    let db = block_on(dataset())?;
+
+    println!("{:>14} {:>14} {:>10} {:>12}", "rows shown", "query", "queries", "per frame");
+    // A loaded page, and the whole table as the list loaded it before paging
+    for rows in [50, SALES] {
+        let (queries, elapsed) = measure(|| block_on(fetch_each(&db, rows)))?;
+        println!("{:>14} {:>14} {:>10} {:>12.2?}", rows, "fetch each", queries, elapsed);
+        let (queries, elapsed) = measure(|| block_on(joined(&db, rows)))?;
+        println!("{:>14} {:>14} {:>10} {:>12.2?}", rows, "joined", queries, elapsed);
+    }
+
+    // The form and filters offer every partner and product and the one type
+    let choices = PARTNERS + PRODUCTS + 1;
+    let (queries, elapsed) = measure(|| block_on(list_choices(&db)))?;
+    println!("{:>14} {:>14} {:>10} {:>12.2?}", choices, "list choices", queries, elapsed);
+    let (queries, elapsed) = measure(|| block_on(cached_choices(&db)))?;
+    println!("{:>14} {:>14} {:>10} {:>12.2?}", choices, "cached choices", queries, elapsed);
+
+    Ok(())
+}
@@ -0,0 +1,38 @@
+CREATE TABLE IF NOT EXISTS partners (
+    partner_type text NOT NULL,
+    partner_name text NOT NULL,
+    director text NOT NULL,
+    email text NOT NULL,
+    phone text NOT NULL,
+    legal_address text NOT NULL,
+    inn text NOT NULL,
+    rating smallint NOT NULL,
+    id text PRIMARY KEY NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS product_types (
+    product_type text PRIMARY KEY NOT NULL,
+    coefficient real NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS products (
+    product_type text NOT NULL,
+    product_name text NOT NULL,
+    article_number text NOT NULL,
+    minimum_cost integer NOT NULL,
+    id text PRIMARY KEY NOT NULL,
+    CONSTRAINT fk_product_type
+    FOREIGN KEY (product_type) REFERENCES product_types(product_type) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS sales (
+    product_id text NOT NULL,
+    quantity integer NOT NULL,
+    sale_date date NOT NULL,
+    partner_id text NOT NULL,
+    id text PRIMARY KEY NOT NULL,
+    CONSTRAINT fk_partner
+    FOREIGN KEY (partner_id) REFERENCES partners(id) ON DELETE CASCADE,
+    CONSTRAINT fk_product
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
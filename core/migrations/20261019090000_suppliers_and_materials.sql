@@ -0,0 +1,28 @@
+CREATE TABLE suppliers (
+    supplier_type text NOT NULL,
+    supplier_name text NOT NULL,
+    inn text NOT NULL,
+    id text PRIMARY KEY NOT NULL
+);
+
+CREATE TABLE materials (
+    material_name text NOT NULL,
+    unit text NOT NULL,
+    price real NOT NULL,
+    defect_percentage real NOT NULL,
+    supplier_id text NOT NULL,
+    id text PRIMARY KEY NOT NULL,
+    CONSTRAINT fk_supplier
+    FOREIGN KEY (supplier_id) REFERENCES suppliers(id) ON DELETE CASCADE
+);
+
+CREATE TABLE product_materials (
+    product_id text NOT NULL,
+    material_id text NOT NULL,
+    quantity real NOT NULL,
+    PRIMARY KEY (product_id, material_id),
+    CONSTRAINT fk_product
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE,
+    CONSTRAINT fk_material
+    FOREIGN KEY (material_id) REFERENCES materials(id) ON DELETE CASCADE
+);
@@ -0,0 +1,11 @@
+CREATE TABLE documents (
+    document_type text NOT NULL,
+    number integer NOT NULL,
+    issue_date date NOT NULL,
+    sale_id text NOT NULL,
+    id text PRIMARY KEY NOT NULL,
+    UNIQUE (document_type, number),
+    UNIQUE (document_type, sale_id),
+    CONSTRAINT fk_sale
+    FOREIGN KEY (sale_id) REFERENCES sales(id) ON DELETE CASCADE
+);
@@ -0,0 +1,12 @@
+CREATE TABLE partner_rating_history (
+    partner_id text NOT NULL,
+    changed_at datetime NOT NULL,
+    old_rating smallint NOT NULL,
+    new_rating smallint NOT NULL,
+    comment text NOT NULL,
+    id text PRIMARY KEY NOT NULL,
+    CONSTRAINT fk_partner
+    FOREIGN KEY (partner_id) REFERENCES partners(id) ON DELETE CASCADE
+);
+
+CREATE INDEX partner_rating_history_partner_id ON partner_rating_history(partner_id, changed_at);
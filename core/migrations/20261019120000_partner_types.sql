@@ -0,0 +1,5 @@
+CREATE TABLE partner_types (
+    partner_type text PRIMARY KEY NOT NULL
+);
+
+INSERT INTO partner_types VALUES ('ООО'), ('ЗАО'), ('ОАО'), ('ПАО'), ('АО'), ('ИП');
@@ -0,0 +1,5 @@
+ALTER TABLE partners ADD COLUMN postal_index text NOT NULL DEFAULT '';
+ALTER TABLE partners ADD COLUMN region text NOT NULL DEFAULT '';
+ALTER TABLE partners ADD COLUMN city text NOT NULL DEFAULT '';
+ALTER TABLE partners ADD COLUMN street text NOT NULL DEFAULT '';
+ALTER TABLE partners ADD COLUMN building text NOT NULL DEFAULT '';
@@ -0,0 +1,53 @@
+-- Full-text indexes of the searchable columns, kept in sync by the triggers below.
+-- `id` links a row of the index back to its record and is not searched.
+CREATE VIRTUAL TABLE partners_search USING fts5(
+    id UNINDEXED,
+    partner_name,
+    director,
+    inn,
+    email,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE VIRTUAL TABLE products_search USING fts5(
+    id UNINDEXED,
+    product_name,
+    article_number,
+    tokenize = 'unicode61 remove_diacritics 2'
+);
+
+CREATE TRIGGER partners_search_insert AFTER INSERT ON partners BEGIN
+    INSERT INTO partners_search (id, partner_name, director, inn, email)
+    VALUES (new.id, new.partner_name, new.director, new.inn, new.email);
+END;
+
+CREATE TRIGGER partners_search_update AFTER UPDATE ON partners BEGIN
+    DELETE FROM partners_search WHERE id = old.id;
+    INSERT INTO partners_search (id, partner_name, director, inn, email)
+    VALUES (new.id, new.partner_name, new.director, new.inn, new.email);
+END;
+
+CREATE TRIGGER partners_search_delete AFTER DELETE ON partners BEGIN
+    DELETE FROM partners_search WHERE id = old.id;
+END;
+
+CREATE TRIGGER products_search_insert AFTER INSERT ON products BEGIN
+    INSERT INTO products_search (id, product_name, article_number)
+    VALUES (new.id, new.product_name, new.article_number);
+END;
+
+CREATE TRIGGER products_search_update AFTER UPDATE ON products BEGIN
+    DELETE FROM products_search WHERE id = old.id;
+    INSERT INTO products_search (id, product_name, article_number)
+    VALUES (new.id, new.product_name, new.article_number);
+END;
+
+CREATE TRIGGER products_search_delete AFTER DELETE ON products BEGIN
+    DELETE FROM products_search WHERE id = old.id;
+END;
+
+INSERT INTO partners_search (id, partner_name, director, inn, email)
+SELECT id, partner_name, director, inn, email FROM partners;
+
+INSERT INTO products_search (id, product_name, article_number)
+SELECT id, product_name, article_number FROM products;
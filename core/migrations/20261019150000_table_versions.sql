@@ -0,0 +1,43 @@
+-- Indexes in the order of the lists, so that a page after a cursor is a seek.
+CREATE INDEX partners_list_order ON partners(partner_name, id);
+CREATE INDEX products_list_order ON products(product_name, id);
+CREATE INDEX sales_list_order ON sales(sale_date DESC, id);
+
+-- Counters of changes to the paged tables, so that a client caching pages
+-- notices changes made anywhere, including cascaded deletes.
+CREATE TABLE table_versions (
+    table_name text PRIMARY KEY NOT NULL,
+    version integer NOT NULL DEFAULT 0
+);
+
+INSERT INTO table_versions (table_name) VALUES ('partners'), ('products'), ('sales');
+
+CREATE TRIGGER partners_version_insert AFTER INSERT ON partners BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'partners';
+END;
+CREATE TRIGGER partners_version_update AFTER UPDATE ON partners BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'partners';
+END;
+CREATE TRIGGER partners_version_delete AFTER DELETE ON partners BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'partners';
+END;
+
+CREATE TRIGGER products_version_insert AFTER INSERT ON products BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'products';
+END;
+CREATE TRIGGER products_version_update AFTER UPDATE ON products BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'products';
+END;
+CREATE TRIGGER products_version_delete AFTER DELETE ON products BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'products';
+END;
+
+CREATE TRIGGER sales_version_insert AFTER INSERT ON sales BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'sales';
+END;
+CREATE TRIGGER sales_version_update AFTER UPDATE ON sales BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'sales';
+END;
+CREATE TRIGGER sales_version_delete AFTER DELETE ON sales BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'sales';
+END;
@@ -0,0 +1,79 @@
+-- Rebuilds `partners` with a foreign key to the `partner_types` dictionary, so
+-- that no client can store a type missing from it. SQLite cannot add the key
+-- to an existing table, and the migrator runs in a transaction, where foreign
+-- keys cannot be turned off: dropping the table deletes the sales, their
+-- documents and the rating history of every partner, so they are put back.
+
+-- Free-text types left over from before the dictionary become entries of it;
+-- the normalization tool maps them onto the entries they were meant to be.
+INSERT OR IGNORE INTO partner_types (partner_type)
+SELECT DISTINCT partner_type FROM partners;
+
+CREATE TABLE partners_new (
+    partner_type text NOT NULL,
+    partner_name text NOT NULL,
+    director text NOT NULL,
+    email text NOT NULL,
+    phone text NOT NULL,
+    legal_address text NOT NULL,
+    inn text NOT NULL,
+    rating smallint NOT NULL,
+    id text PRIMARY KEY NOT NULL,
+    postal_index text NOT NULL DEFAULT '',
+    region text NOT NULL DEFAULT '',
+    city text NOT NULL DEFAULT '',
+    street text NOT NULL DEFAULT '',
+    building text NOT NULL DEFAULT '',
+    CONSTRAINT fk_partner_type
+    FOREIGN KEY (partner_type) REFERENCES partner_types(partner_type) ON UPDATE CASCADE
+);
+
+INSERT INTO partners_new (partner_type, partner_name, director, email, phone, legal_address, inn, rating, id,
+    postal_index, region, city, street, building)
+SELECT partner_type, partner_name, director, email, phone, legal_address, inn, rating, id,
+    postal_index, region, city, street, building
+FROM partners;
+
+CREATE TEMPORARY TABLE saved_sales AS SELECT * FROM sales;
+CREATE TEMPORARY TABLE saved_documents AS SELECT * FROM documents;
+CREATE TEMPORARY TABLE saved_partner_rating_history AS SELECT * FROM partner_rating_history;
+
+DROP TABLE partners;
+ALTER TABLE partners_new RENAME TO partners;
+
+INSERT INTO sales SELECT * FROM saved_sales;
+INSERT INTO documents SELECT * FROM saved_documents;
+INSERT INTO partner_rating_history SELECT * FROM saved_partner_rating_history;
+DROP TABLE saved_sales;
+DROP TABLE saved_documents;
+DROP TABLE saved_partner_rating_history;
+
+-- Indexes and triggers of the old table went with it. Its rows stay in
+-- `partners_search`: the implicit delete of a dropped table fires no triggers.
+CREATE INDEX partners_list_order ON partners(partner_name, id);
+CREATE INDEX partners_partner_type ON partners(partner_type);
+
+CREATE TRIGGER partners_search_insert AFTER INSERT ON partners BEGIN
+    INSERT INTO partners_search (id, partner_name, director, inn, email)
+    VALUES (new.id, new.partner_name, new.director, new.inn, new.email);
+END;
+
+CREATE TRIGGER partners_search_update AFTER UPDATE ON partners BEGIN
+    DELETE FROM partners_search WHERE id = old.id;
+    INSERT INTO partners_search (id, partner_name, director, inn, email)
+    VALUES (new.id, new.partner_name, new.director, new.inn, new.email);
+END;
+
+CREATE TRIGGER partners_search_delete AFTER DELETE ON partners BEGIN
+    DELETE FROM partners_search WHERE id = old.id;
+END;
+
+CREATE TRIGGER partners_version_insert AFTER INSERT ON partners BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'partners';
+END;
+CREATE TRIGGER partners_version_update AFTER UPDATE ON partners BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'partners';
+END;
+CREATE TRIGGER partners_version_delete AFTER DELETE ON partners BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'partners';
+END;
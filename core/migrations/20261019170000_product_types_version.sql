@@ -0,0 +1,12 @@
+-- Product types are cached by the Sales view alongside partners and products.
+INSERT INTO table_versions (table_name) VALUES ('product_types');
+
+CREATE TRIGGER product_types_version_insert AFTER INSERT ON product_types BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'product_types';
+END;
+CREATE TRIGGER product_types_version_update AFTER UPDATE ON product_types BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'product_types';
+END;
+CREATE TRIGGER product_types_version_delete AFTER DELETE ON product_types BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'product_types';
+END;
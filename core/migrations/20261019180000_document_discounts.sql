@@ -0,0 +1,3 @@
+-- Discount in percent a document was issued with, so that a reprint shows the
+-- same amounts. Documents issued before have none until they are printed again.
+ALTER TABLE documents ADD COLUMN discount integer;
@@ -0,0 +1,8 @@
+-- The "город" or "г." the city of the legal address was written with, so the
+-- address is written back the same way. Taken from the addresses split before.
+ALTER TABLE partners ADD COLUMN settlement text NOT NULL DEFAULT '';
+
+UPDATE partners SET settlement = 'город'
+WHERE city <> '' AND instr(legal_address, ', город ' || city || ',') > 0;
+UPDATE partners SET settlement = 'г.'
+WHERE city <> '' AND (instr(legal_address, ', г. ' || city || ',') > 0 OR instr(legal_address, ', г.' || city || ',') > 0);
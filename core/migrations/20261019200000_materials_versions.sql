@@ -0,0 +1,32 @@
+-- The Materials view caches its lists alongside products, as the Sales view does.
+INSERT INTO table_versions (table_name) VALUES ('suppliers'), ('materials'), ('product_materials');
+
+CREATE TRIGGER suppliers_version_insert AFTER INSERT ON suppliers BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'suppliers';
+END;
+CREATE TRIGGER suppliers_version_update AFTER UPDATE ON suppliers BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'suppliers';
+END;
+CREATE TRIGGER suppliers_version_delete AFTER DELETE ON suppliers BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'suppliers';
+END;
+
+CREATE TRIGGER materials_version_insert AFTER INSERT ON materials BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'materials';
+END;
+CREATE TRIGGER materials_version_update AFTER UPDATE ON materials BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'materials';
+END;
+CREATE TRIGGER materials_version_delete AFTER DELETE ON materials BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'materials';
+END;
+
+CREATE TRIGGER product_materials_version_insert AFTER INSERT ON product_materials BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'product_materials';
+END;
+CREATE TRIGGER product_materials_version_update AFTER UPDATE ON product_materials BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'product_materials';
+END;
+CREATE TRIGGER product_materials_version_delete AFTER DELETE ON product_materials BEGIN
+    UPDATE table_versions SET version = version + 1 WHERE table_name = 'product_materials';
+END;
@@ -0,0 +1,239 @@
+//! Errors of the domain logic. Failed SQLite constraints become variants
+//! naming the table and columns involved, and [`Display`](std::fmt::Display)
+//! turns those into Russian names of the record and the field. The SQLite
+//! error stays their [`source`](std::error::Error::source) for support.
+
+use std::fmt;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+// Extended result codes of failed constraints, https://www.sqlite.org/rescode.html
+const SQLITE_CONSTRAINT_FOREIGNKEY: &str = "787";
+const SQLITE_CONSTRAINT_NOTNULL: &str = "1299";
+const SQLITE_CONSTRAINT_PRIMARYKEY: &str = "1555";
+const SQLITE_CONSTRAINT_UNIQUE: &str = "2067";
+
+#[derive(Debug)]
+pub enum Error {
+    /// Another record of `table` already has the same values of `columns`.
+    UniqueViolation { table: String, columns: Vec<String>, source: Option<Box<sqlx::Error>> },
+    /// A record refers to one that does not exist or, `in_use`, a deleted record
+    /// is still referred to by `column` of `table`. SQLite does not say which,
+    /// [`Repository`](crate::models::repository::Repository) fills in what it knows.
+    ForeignKeyViolation {
+        table: Option<String>,
+        column: Option<String>,
+        in_use: bool,
+        source: Option<Box<sqlx::Error>>,
+    },
+    /// No record with the key.
+    NotFound { table: Option<String>, key: Option<String> },
+    /// The value of `column` is missing or breaks a rule of the model.
+    Validation { table: String, column: String, rule: Rule, source: Option<Box<sqlx::Error>> },
+    DatabaseError(sqlx::Error),
+    Any(String),
+}
+
+/// A rule of the model a value breaks. [`Display`](std::fmt::Display) words it
+/// in Russian for the CLI and the API, the interface translates it itself.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Rule {
+    /// The value is missing or blank.
+    Required,
+    /// An address is not the five parts "index, region, city, street, building".
+    AddressParts { found: usize },
+    /// A postal index is not six digits.
+    PostalIndex { index: String },
+    /// A partner is merged with itself.
+    DistinctMerge,
+    /// A quantity is zero or negative.
+    Positive,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::Required => write!(f, "обязательно для заполнения"),
+            Rule::AddressParts { found } => write!(
+                f,
+                "Ожидается 5 частей через запятую (индекс, регион, город, улица, дом), найдено {}",
+                found
+            ),
+            Rule::PostalIndex { index } => write!(f, "Индекс \"{}\" должен состоять из 6 цифр", index),
+            Rule::DistinctMerge => write!(f, "Нельзя объединить партнера с самим собой"),
+            Rule::Positive => write!(f, "должно быть больше нуля"),
+        }
+    }
+}
+
+impl Error {
+    pub fn validation(table: &str, column: &str, rule: Rule) -> Self {
+        Error::Validation { table: table.to_string(), column: column.to_string(), rule, source: None }
+    }
+}
+
+impl From<sqlx::Error> for Error {
+    fn from(error: sqlx::Error) -> Self {
+        match &error {
+            sqlx::Error::RowNotFound => Error::NotFound { table: None, key: None },
+            sqlx::Error::Database(_) => constraint_error(error),
+            _ => Error::DatabaseError(error),
+        }
+    }
+}
+
+/// The variant of a failed constraint, from its code and the "table.column"
+/// list SQLite puts at the end of the message. Other errors are kept as they are.
+fn constraint_error(error: sqlx::Error) -> Error {
+    let sqlx::Error::Database(e) = &error else {
+        return Error::DatabaseError(error);
+    };
+    let code = e.code().map(|code| code.into_owned());
+    let mut columns: Vec<(String, String)> = e
+        .message()
+        .split_once(": ")
+        .map(|(_, columns)| {
+            columns
+                .split(", ")
+                .filter_map(|column| column.split_once('.'))
+                .map(|(table, column)| (table.to_string(), column.to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+    let source = Box::new(error);
+
+    match code.as_deref() {
+        Some(SQLITE_CONSTRAINT_PRIMARYKEY | SQLITE_CONSTRAINT_UNIQUE) if !columns.is_empty() => Error::UniqueViolation {
+            table: columns[0].0.clone(),
+            columns: columns.into_iter().map(|(_, column)| column).collect(),
+            source: Some(source),
+        },
+        Some(SQLITE_CONSTRAINT_FOREIGNKEY) => {
+            Error::ForeignKeyViolation { table: None, column: None, in_use: false, source: Some(source) }
+        }
+        Some(SQLITE_CONSTRAINT_NOTNULL) if !columns.is_empty() => {
+            let (table, column) = columns.swap_remove(0);
+            Error::Validation { table, column, rule: Rule::Required, source: Some(source) }
+        }
+        _ => Error::DatabaseError(*source),
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UniqueViolation { table, columns, .. } => {
+                let fields: Vec<String> = columns
+                    .iter()
+                    .map(|column| ["«", field_label(table, column), "»"].concat())
+                    .collect();
+                write!(
+                    f,
+                    "{}: запись с таким значением {} {} уже существует",
+                    record_label(table),
+                    if fields.len() == 1 { "поля" } else { "полей" },
+                    fields.join(", ")
+                )
+            }
+            Error::ForeignKeyViolation { table: Some(table), column: Some(column), in_use: true, .. } => write!(
+                f,
+                "{}: в поле «{}» указана удаляемая запись",
+                record_label(table),
+                field_label(table, column)
+            ),
+            Error::ForeignKeyViolation { table: Some(table), column: Some(column), .. } => write!(
+                f,
+                "{}: в поле «{}» указана несуществующая запись",
+                record_label(table),
+                field_label(table, column)
+            ),
+            Error::ForeignKeyViolation { table: Some(table), column: None, .. } => {
+                write!(f, "{}: указана несуществующая связанная запись", record_label(table))
+            }
+            Error::ForeignKeyViolation { table: None, .. } => write!(f, "Указана несуществующая связанная запись"),
+            Error::NotFound { table: Some(table), key: Some(key) } => {
+                write!(f, "{}: запись «{}» не найдена", record_label(table), key)
+            }
+            Error::NotFound { table: Some(table), key: None } => write!(f, "{}: запись не найдена", record_label(table)),
+            Error::NotFound { table: None, .. } => write!(f, "Запись не найдена"),
+            Error::Validation { table, column, rule, .. } => write!(
+                f,
+                "{}, поле «{}»: {}",
+                record_label(table),
+                field_label(table, column),
+                rule
+            ),
+            Error::DatabaseError(err) => write!(f, "Ошибка при выполнении запроса:\n{}", err),
+            Error::Any(msg) => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UniqueViolation { source, .. }
+            | Error::ForeignKeyViolation { source, .. }
+            | Error::Validation { source, .. } => source.as_deref().map(|source| source as _),
+            Error::DatabaseError(error) => Some(error),
+            Error::NotFound { .. } | Error::Any(_) => None,
+        }
+    }
+}
+
+/// What a record of `table` is called in the interface.
+fn record_label(table: &str) -> &str {
+    match table {
+        "partners" => "Партнер",
+        "partner_types" => "Тип организации",
+        "partner_rating_history" => "Изменение рейтинга",
+        "products" => "Продукт",
+        "product_types" => "Тип продукта",
+        "product_materials" => "Состав продукта",
+        "materials" => "Материал",
+        "suppliers" => "Поставщик",
+        "sales" => "Продажа",
+        "documents" => "Документ",
+        _ => table,
+    }
+}
+
+/// What `column` of `table` is called in the forms.
+fn field_label<'a>(table: &str, column: &'a str) -> &'a str {
+    match (table, column) {
+        ("partners" | "suppliers", "partner_name" | "supplier_name") => "Название организации",
+        ("partners" | "suppliers" | "partner_types", "partner_type" | "supplier_type") => "Тип организации",
+        (_, "product_name" | "material_name") => "Название",
+        (_, "id") => "Идентификатор",
+        (_, "director") => "Директор",
+        (_, "email") => "E-Mail",
+        (_, "phone") => "Телефон",
+        (_, "legal_address") => "Юридический адрес",
+        (_, "inn") => "ИНН",
+        (_, "rating" | "old_rating" | "new_rating") => "Рейтинг",
+        (_, "comment") => "Причина изменения",
+        (_, "postal_index") => "Индекс",
+        (_, "region") => "Регион",
+        (_, "city") => "Город",
+        (_, "street") => "Улица",
+        (_, "building") => "Дом",
+        (_, "product_type") => "Тип продукта",
+        (_, "coefficient") => "Коэффициент",
+        (_, "article_number") => "Артикул",
+        (_, "minimum_cost") => "Минимальная цена",
+        (_, "unit") => "Единица измерения",
+        (_, "price") => "Цена за единицу",
+        (_, "defect_percentage") => "Процент брака",
+        (_, "quantity") => "Количество",
+        (_, "sale_date") => "Дата продажи",
+        (_, "document_type") => "Тип документа",
+        (_, "number") => "Номер",
+        (_, "issue_date") => "Дата выдачи",
+        (_, "product_id") => "Продукт",
+        (_, "partner_id") => "Партнер",
+        (_, "material_id") => "Материал",
+        (_, "supplier_id") => "Поставщик",
+        (_, "sale_id") => "Продажа",
+        _ => column,
+    }
+}
@@ -0,0 +1,32 @@
+//! Domain logic of the application: the models with their CRUD operations and
+//! calculations, printed documents and the database setup. The egui front-end
+//! (`interface`) and any other tool share it by linking this crate.
+
+mod error;
+pub mod models;
+pub mod printing;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+
+pub use error::{Error, Result, Rule};
+
+use std::str::FromStr;
+
+use sqlx::{migrate::Migrator, sqlite::SqliteConnectOptions, SqlitePool};
+
+/// Database the applications open unless told otherwise.
+pub const DEFAULT_DATABASE_URL: &str = "sqlite://data.sqlite";
+
+/// Schema migrations, applied by [`connect`]. Use it directly to set up a
+/// pool created some other way, e.g. an in-memory database.
+pub static MIGRATOR: Migrator = sqlx::migrate!();
+
+/// Opens the database at `url` (e.g. `sqlite://data.sqlite`), creating the
+/// file on first start, and brings its schema up to date.
+pub async fn connect(url: &str) -> Result<SqlitePool> {
+    let db = SqlitePool::connect_with(SqliteConnectOptions::from_str(url)?.create_if_missing(true)).await?;
+
+    MIGRATOR.run(&db).await.map_err(sqlx::Error::from)?;
+
+    Ok(db)
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, Result, Rule};
+
+/// Legal address split into its parts, e.g.
+/// "652050, Кемеровская область, город Юрга, ул. Лесная, 15".
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Address {
+    pub postal_index: String,
+    pub region: String,
+    /// The "город" or "г." the city was written with, kept to write it back.
+    /// Other settlement types stay a part of `city`.
+    #[serde(default)]
+    pub settlement: String,
+    pub city: String,
+    pub street: String,
+    pub building: String,
+}
+
+impl Address {
+    /// Parses "index, region, city, street, building". The city may be
+    /// prefixed with "город" or "г.", which goes to `settlement`.
+    pub fn parse(address: &str) -> Result<Address> {
+        let parts: Vec<&str> = address
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+
+        let [postal_index, region, city, street, building] = parts[..] else {
+            return Err(Error::validation("partners", "legal_address", Rule::AddressParts { found: parts.len() }));
+        };
+
+        let (settlement, city) = ["город", "г."]
+            .iter()
+            .find_map(|prefix| Some((*prefix, strip_settlement(city, prefix)?)))
+            .unwrap_or(("", city));
+
+        let address = Address {
+            postal_index: postal_index.to_string(),
+            region: region.to_string(),
+            settlement: settlement.to_string(),
+            city: city.to_string(),
+            street: street.to_string(),
+            building: building.to_string(),
+        };
+        address.validate()?;
+
+        Ok(address)
+    }
+
+    /// Checks that every part is filled in and the index is 6 digits.
+    pub fn validate(&self) -> Result<()> {
+        let parts = [
+            ("postal_index", &self.postal_index),
+            ("region", &self.region),
+            ("city", &self.city),
+            ("street", &self.street),
+            ("building", &self.building),
+        ];
+        if let Some((column, _)) = parts.iter().find(|(_, part)| part.trim().is_empty()) {
+            return Err(Error::validation("partners", column, Rule::Required));
+        }
+
+        if self.postal_index.chars().count() != 6 || !self.postal_index.chars().all(|c| c.is_ascii_digit()) {
+            return Err(Error::validation(
+                "partners",
+                "postal_index",
+                Rule::PostalIndex { index: self.postal_index.clone() },
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// The name after the settlement type `kind`, which is a word of its own
+/// unless abbreviated with a dot: "город Юрга", "г.Юрга", but not "Городец".
+fn strip_settlement<'a>(city: &'a str, kind: &str) -> Option<&'a str> {
+    let rest = city.strip_prefix(kind)?;
+    let name = rest.trim_start();
+    (!name.is_empty() && (kind.ends_with('.') || name.len() < rest.len())).then_some(name)
+}
+
+impl std::fmt::Display for Address {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}, {}, ", self.postal_index, self.region)?;
+        if !self.settlement.is_empty() {
+            write!(f, "{} ", self.settlement)?;
+        }
+        write!(f, "{}, {}, {}", self.city, self.street, self.building)
+    }
+}
@@ -0,0 +1,86 @@
+use chrono::NaiveDate;
+use serde::Serialize;
+use sqlx::{query_as, query_scalar, SqliteConnection, SqlitePool};
+
+use crate::Result;
+
+/// Total quantities sold to a partner from which a discount applies, with the
+/// discount in percent, from the smallest. Below the first there is none.
+pub const DISCOUNT_STEPS: [(i64, i64); 3] = [(10_000, 5), (50_000, 10), (300_000, 15)];
+
+/// Discount of a partner in percent, by the total quantity of products sold to it.
+pub fn discount_percent(total_quantity: i64) -> i64 {
+    DISCOUNT_STEPS
+        .iter()
+        .rev()
+        .find(|(from, _)| total_quantity >= *from)
+        .map_or(0, |(_, discount)| *discount)
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct PartnerDiscount {
+    pub partner_id: String,
+    pub partner_name: String,
+    pub total_quantity: i64,
+    pub discount: i64,
+}
+
+/// Discounts of every partner, including the ones without sales.
+pub async fn get_all(db: &SqlitePool) -> Result<Vec<PartnerDiscount>> {
+    struct Row {
+        partner_id: String,
+        partner_name: String,
+        total_quantity: i64,
+    }
+
+    let rows = query_as!(
+        Row,
+        r#"SELECT
+            partners.id AS "partner_id!",
+            partners.partner_name AS "partner_name!",
+            COALESCE(SUM(sales.quantity), 0) AS "total_quantity!: i64"
+        FROM partners
+        LEFT JOIN sales ON sales.partner_id = partners.id
+        GROUP BY partners.id
+        ORDER BY partners.partner_name"#
+    )
+    .fetch_all(db)
+    .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PartnerDiscount {
+            discount: discount_percent(row.total_quantity),
+            partner_id: row.partner_id,
+            partner_name: row.partner_name,
+            total_quantity: row.total_quantity,
+        })
+        .collect())
+}
+
+/// Discount of a single partner, as [`get_all`] computes it.
+pub async fn get(db: &SqlitePool, partner_id: &str) -> Result<i64> {
+    let total_quantity = query_scalar!(
+        r#"SELECT COALESCE(SUM(quantity), 0) AS "total_quantity!: i64" FROM sales WHERE partner_id = $1"#,
+        partner_id
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(discount_percent(total_quantity))
+}
+
+/// Discount of a partner by the sales made up to and including `date`, the
+/// one a document for a sale of that day is issued with.
+pub async fn get_as_of(db: &mut SqliteConnection, partner_id: &str, date: NaiveDate) -> Result<i64> {
+    let total_quantity = query_scalar!(
+        r#"SELECT COALESCE(SUM(quantity), 0) AS "total_quantity!: i64"
+        FROM sales WHERE partner_id = $1 AND sale_date <= $2"#,
+        partner_id,
+        date
+    )
+    .fetch_one(db)
+    .await?;
+
+    Ok(discount_percent(total_quantity))
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use chrono::NaiveDate;
+use sqlx::{query, query_as, SqlitePool};
+use uuid::Uuid;
+
+use crate::models::discounts;
+use crate::Result;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DocumentType {
+    Invoice,
+    DeliveryNote,
+}
+
+impl DocumentType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            DocumentType::Invoice => "invoice",
+            DocumentType::DeliveryNote => "delivery_note",
+        }
+    }
+}
+
+/// Title of the document as printed, in Russian; the interface translates it itself.
+impl fmt::Display for DocumentType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DocumentType::Invoice => write!(f, "Счёт"),
+            DocumentType::DeliveryNote => write!(f, "Товарная накладная"),
+        }
+    }
+}
+
+/// A printed document issued for a sale. Numbers are sequential per document type.
+#[derive(Clone, Debug)]
+pub struct Document {
+    pub document_type: String,
+    pub number: i64,
+    pub issue_date: NaiveDate,
+    pub sale_id: String,
+    pub id: String,
+    /// Partner discount in percent the amounts are printed with.
+    pub discount: i64,
+}
+
+/// Returns the document of the given type issued for the sale, issuing a new
+/// one with the next number if the sale has not been printed before. The
+/// discount is the partner's by the sales up to the day of the sale and stays
+/// with the document, so later sales do not change a reprint.
+pub async fn get_or_issue(
+    db: &SqlitePool,
+    document_type: DocumentType,
+    sale_id: String,
+    issue_date: NaiveDate,
+) -> Result<Document> {
+    let document_type = document_type.as_str();
+    let mut transaction = db.begin().await?;
+
+    let sale = query!(
+        r#"SELECT partner_id, sale_date AS "sale_date: NaiveDate" FROM sales WHERE id = $1"#,
+        sale_id
+    )
+    .fetch_one(&mut *transaction)
+    .await?;
+    let discount = discounts::get_as_of(&mut transaction, &sale.partner_id, sale.sale_date).await?;
+
+    // Documents issued before discounts were kept get theirs now
+    query!(
+        "UPDATE documents SET discount = $1 WHERE document_type = $2 AND sale_id = $3 AND discount IS NULL",
+        discount,
+        document_type,
+        sale_id
+    )
+    .execute(&mut *transaction)
+    .await?;
+    let existing = query_as!(
+        Document,
+        r#"SELECT document_type, number, issue_date AS "issue_date: NaiveDate", sale_id, id, discount AS "discount!"
+        FROM documents WHERE document_type = $1 AND sale_id = $2"#,
+        document_type,
+        sale_id
+    )
+    .fetch_optional(&mut *transaction)
+    .await?;
+
+    let document = match existing {
+        Some(document) => document,
+        None => {
+            let id = Uuid::new_v4().to_string();
+            query_as!(
+                Document,
+                r#"INSERT INTO documents
+                SELECT $1, COALESCE(MAX(number), 0) + 1, $2, $3, $4, $5
+                FROM documents WHERE document_type = $1
+                RETURNING document_type, number, issue_date AS "issue_date: NaiveDate", sale_id, id,
+                    discount AS "discount!""#,
+                document_type,
+                issue_date,
+                sale_id,
+                id,
+                discount
+            )
+            .fetch_one(&mut *transaction)
+            .await?
+        }
+    };
+
+    transaction.commit().await?;
+
+    Ok(document)
+}
@@ -0,0 +1,309 @@
+use std::fmt;
+
+use chrono::Utc;
+use sqlx::{types::Uuid, SqliteConnection, SqlitePool};
+
+use super::partners::Partner;
+use super::repository::Repository;
+use crate::Result;
+
+/// Names and emails at least this similar are reported as possible duplicates.
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// An existing partner that looks like the same organization as another one.
+#[derive(Clone)]
+pub struct Duplicate {
+    pub partner: Partner,
+    pub reasons: Vec<DuplicateReason>,
+}
+
+/// What two partners have in common. [`Display`](std::fmt::Display) words it
+/// in Russian for the CLI and the API, the interface translates it itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DuplicateReason {
+    SameInn,
+    SimilarName,
+    SimilarEmail,
+}
+
+impl fmt::Display for DuplicateReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DuplicateReason::SameInn => write!(f, "совпадает ИНН"),
+            DuplicateReason::SimilarName => write!(f, "похожее название"),
+            DuplicateReason::SimilarEmail => write!(f, "похожий E-Mail"),
+        }
+    }
+}
+
+/// Existing partners that may be the same organization as `candidate`:
+/// same INN, or a similar name or email.
+pub async fn find_duplicates(db: &SqlitePool, candidate: &Partner) -> Result<Vec<Duplicate>> {
+    Ok(Partner::list(db, &[], None)
+        .await?
+        .into_iter()
+        .filter(|partner| partner.id != candidate.id)
+        .filter_map(|partner| {
+            duplicate_reasons(candidate, &partner).map(|reasons| Duplicate { partner, reasons })
+        })
+        .collect())
+}
+
+/// Every pair of existing partners that look like duplicates of each other.
+pub async fn find_all_duplicates(db: &SqlitePool) -> Result<Vec<(Partner, Duplicate)>> {
+    let partners = Partner::list(db, &[], None).await?;
+
+    let mut pairs = vec![];
+    for (i, first) in partners.iter().enumerate() {
+        for second in &partners[i + 1..] {
+            if let Some(reasons) = duplicate_reasons(first, second) {
+                pairs.push((
+                    first.clone(),
+                    Duplicate {
+                        partner: second.clone(),
+                        reasons,
+                    },
+                ));
+            }
+        }
+    }
+
+    Ok(pairs)
+}
+
+fn duplicate_reasons(first: &Partner, second: &Partner) -> Option<Vec<DuplicateReason>> {
+    let mut reasons = vec![];
+
+    let inn = |partner: &Partner| -> String {
+        partner.inn.chars().filter(|c| !c.is_whitespace()).collect()
+    };
+    if !inn(first).is_empty() && inn(first) == inn(second) {
+        reasons.push(DuplicateReason::SameInn);
+    }
+
+    let name = |partner: &Partner| normalize_name(&partner.partner_name);
+    if !name(first).is_empty() && similarity(&name(first), &name(second)) >= SIMILARITY_THRESHOLD {
+        reasons.push(DuplicateReason::SimilarName);
+    }
+
+    let email = |partner: &Partner| partner.email.trim().to_lowercase();
+    if !email(first).is_empty() && similarity(&email(first), &email(second)) >= SIMILARITY_THRESHOLD {
+        reasons.push(DuplicateReason::SimilarEmail);
+    }
+
+    (!reasons.is_empty()).then_some(reasons)
+}
+
+/// Lower-cases the name and drops quotes and punctuation, so that
+/// "Паркет 29" and «паркет-29» compare equal.
+fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Levenshtein distance scaled to 0.0 (nothing in common) - 1.0 (equal).
+fn similarity(first: &str, second: &str) -> f64 {
+    let first: Vec<char> = first.chars().collect();
+    let second: Vec<char> = second.chars().collect();
+
+    let longest = first.len().max(second.len());
+    if longest == 0 {
+        return 1.0;
+    }
+
+    let mut previous: Vec<usize> = (0..=second.len()).collect();
+    for (i, a) in first.iter().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in second.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    1.0 - previous[second.len()] as f64 / longest as f64
+}
+
+/// Partner fields the merge tool lets the user pick one by one. [`Display`](std::fmt::Display)
+/// names them in Russian, the interface translates them itself.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PartnerField {
+    PartnerType,
+    PartnerName,
+    Director,
+    Email,
+    Phone,
+    Address,
+    Inn,
+    Rating,
+}
+
+impl PartnerField {
+    pub const ALL: [PartnerField; 8] = [
+        PartnerField::PartnerType,
+        PartnerField::PartnerName,
+        PartnerField::Director,
+        PartnerField::Email,
+        PartnerField::Phone,
+        PartnerField::Address,
+        PartnerField::Inn,
+        PartnerField::Rating,
+    ];
+
+    pub fn value(&self, partner: &Partner) -> String {
+        match self {
+            PartnerField::PartnerType => partner.partner_type.clone(),
+            PartnerField::PartnerName => partner.partner_name.clone(),
+            PartnerField::Director => partner.director.clone(),
+            PartnerField::Email => partner.email.clone(),
+            PartnerField::Phone => partner.phone.clone(),
+            PartnerField::Address => partner.legal_address.clone(),
+            PartnerField::Inn => partner.inn.clone(),
+            PartnerField::Rating => partner.rating.to_string(),
+        }
+    }
+
+    fn copy(&self, target: &mut Partner, source: &Partner) {
+        match self {
+            PartnerField::PartnerType => target.partner_type = source.partner_type.clone(),
+            PartnerField::PartnerName => target.partner_name = source.partner_name.clone(),
+            PartnerField::Director => target.director = source.director.clone(),
+            PartnerField::Email => target.email = source.email.clone(),
+            PartnerField::Phone => target.phone = source.phone.clone(),
+            PartnerField::Address => {
+                target.legal_address = source.legal_address.clone();
+                target.postal_index = source.postal_index.clone();
+                target.region = source.region.clone();
+                target.settlement = source.settlement.clone();
+                target.city = source.city.clone();
+                target.street = source.street.clone();
+                target.building = source.building.clone();
+            }
+            PartnerField::Inn => target.inn = source.inn.clone(),
+            PartnerField::Rating => target.rating = source.rating,
+        }
+    }
+}
+
+impl fmt::Display for PartnerField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self {
+            PartnerField::PartnerType => "Тип организации",
+            PartnerField::PartnerName => "Название организации",
+            PartnerField::Director => "Директор",
+            PartnerField::Email => "E-Mail",
+            PartnerField::Phone => "Телефон",
+            PartnerField::Address => "Адрес",
+            PartnerField::Inn => "ИНН",
+            PartnerField::Rating => "Рейтинг",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Merges `duplicate` into `survivor` in a single transaction: the survivor
+/// takes the listed fields from the duplicate, the duplicate's sales and
+/// rating history move to the survivor and the duplicate is deleted. Ratings
+/// are the stored ones, so a change made since the partners were loaded is
+/// neither reverted nor recorded from the wrong rating.
+pub async fn merge(
+    db: &SqlitePool,
+    survivor: &Partner,
+    duplicate: &Partner,
+    from_duplicate: &[PartnerField],
+) -> Result<Partner> {
+    if survivor.id == duplicate.id {
+        return Err(crate::Error::validation("partners", "id", crate::Rule::DistinctMerge));
+    }
+
+    let mut transaction = db.begin().await?;
+
+    let survivor = Partner { rating: stored_rating(&mut transaction, &survivor.id).await?, ..survivor.clone() };
+    let duplicate = Partner { rating: stored_rating(&mut transaction, &duplicate.id).await?, ..duplicate.clone() };
+
+    let mut merged = survivor.clone();
+    from_duplicate
+        .iter()
+        .for_each(|field| field.copy(&mut merged, &duplicate));
+
+    sqlx::query!(
+        "UPDATE sales SET partner_id = $1 WHERE partner_id = $2",
+        survivor.id,
+        duplicate.id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    sqlx::query!(
+        "UPDATE partner_rating_history SET partner_id = $1 WHERE partner_id = $2",
+        survivor.id,
+        duplicate.id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    if merged.rating != survivor.rating {
+        let changed_at = Utc::now();
+        let comment = format!("Объединение с дубликатом \"{}\"", duplicate.partner_name);
+        let change_id = Uuid::new_v4().to_string();
+        sqlx::query!(
+            "INSERT INTO partner_rating_history VALUES ($1,$2,$3,$4,$5,$6)",
+            survivor.id,
+            changed_at,
+            survivor.rating,
+            merged.rating,
+            comment,
+            change_id
+        )
+        .execute(&mut *transaction)
+        .await?;
+    }
+
+    sqlx::query!("DELETE FROM partners WHERE id = $1", duplicate.id)
+        .execute(&mut *transaction)
+        .await?;
+
+    sqlx::query!(
+        r#"UPDATE partners SET
+            partner_type = $1, partner_name = $2, director = $3, email = $4, phone = $5,
+            legal_address = $6, inn = $7, rating = $8, postal_index = $9, region = $10,
+            settlement = $11, city = $12, street = $13, building = $14
+        WHERE id = $15"#,
+        merged.partner_type,
+        merged.partner_name,
+        merged.director,
+        merged.email,
+        merged.phone,
+        merged.legal_address,
+        merged.inn,
+        merged.rating,
+        merged.postal_index,
+        merged.region,
+        merged.settlement,
+        merged.city,
+        merged.street,
+        merged.building,
+        merged.id
+    )
+    .execute(&mut *transaction)
+    .await?;
+
+    transaction.commit().await?;
+
+    Ok(merged)
+}
+
+async fn stored_rating(db: &mut SqliteConnection, id: &str) -> Result<i64> {
+    sqlx::query_scalar!("SELECT rating FROM partners WHERE id = $1", id)
+        .fetch_optional(db)
+        .await?
+        .ok_or_else(|| crate::Error::NotFound {
+            table: Some("partners".into()),
+            key: Some(id.to_string()),
+        })
+}
@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{query, query_as, sqlite::SqliteQueryResult, SqlitePool};
+use uuid::Uuid;
+
+use crate::Result;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Material {
+    pub material_name: String,
+    pub unit: String,
+    pub price: f64,
+    /// Share of the material lost to defects, in percent.
+    pub defect_percentage: f64,
+    pub supplier_id: String,
+    pub id: String,
+}
+
+impl Default for Material {
+    fn default() -> Self {
+        Material::new("".to_string(), "".to_string(), 0.0, 0.0, "".to_string())
+    }
+}
+
+impl PartialEq for Material {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Material {
+    pub fn new(
+        material_name: String,
+        unit: String,
+        price: f64,
+        defect_percentage: f64,
+        supplier_id: String,
+    ) -> Self {
+        Material {
+            material_name,
+            unit,
+            price,
+            defect_percentage,
+            supplier_id,
+            id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Price of one usable unit, accounting for the defect percentage.
+    pub fn effective_price(&self) -> f64 {
+        self.price * (1.0 + self.defect_percentage / 100.0)
+    }
+
+    pub async fn create(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!(
+            "INSERT INTO materials VALUES ($1,$2,$3,$4,$5,$6)",
+            self.material_name,
+            self.unit,
+            self.price,
+            self.defect_percentage,
+            self.supplier_id,
+            self.id
+        )
+        .execute(db)
+        .await
+        .map_err(|e| e.into())
+    }
+
+    pub async fn delete(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!("DELETE FROM materials WHERE id = $1", self.id)
+            .execute(db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+pub async fn get(db: &SqlitePool, id: String) -> Result<Material> {
+    query_as!(Material, "SELECT * FROM materials WHERE id = $1", id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn get_all(db: &SqlitePool) -> Result<Vec<Material>> {
+    query_as!(Material, "SELECT * FROM materials")
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.into())
+}
@@ -0,0 +1,15 @@
+pub mod partners;
+pub mod sales;
+pub mod products;
+pub mod product_types;
+pub mod suppliers;
+pub mod materials;
+pub mod product_materials;
+pub mod documents;
+pub mod partner_rating_history;
+pub mod partner_types;
+pub mod address;
+pub mod duplicates;
+pub mod discounts;
+pub mod repository;
+pub mod search;
@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use sqlx::{query_as, SqlitePool};
+
+use crate::Result;
+
+/// A single change of `Partner::rating`, recorded by `Partner::change_rating`.
+#[derive(Clone, Debug)]
+pub struct RatingChange {
+    pub partner_id: String,
+    pub changed_at: DateTime<Utc>,
+    pub old_rating: i64,
+    pub new_rating: i64,
+    pub comment: String,
+    pub id: String,
+}
+
+/// Rating changes of the partner, oldest first.
+pub async fn get_by_partner(db: &SqlitePool, partner_id: String) -> Result<Vec<RatingChange>> {
+    query_as!(
+        RatingChange,
+        r#"SELECT partner_id, changed_at AS "changed_at: DateTime<Utc>", old_rating, new_rating, comment, id
+        FROM partner_rating_history WHERE partner_id = $1 ORDER BY changed_at"#,
+        partner_id
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.into())
+}
@@ -0,0 +1,148 @@
+use sqlx::{query, query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::Result;
+
+#[derive(Clone, Debug, Default)]
+pub struct PartnerType {
+    pub partner_type: String,
+}
+
+impl PartialEq for PartnerType {
+    fn eq(&self, other: &Self) -> bool {
+        self.partner_type == other.partner_type
+    }
+}
+
+impl PartnerType {
+    pub fn new(partner_type: String) -> Self {
+        PartnerType { partner_type }
+    }
+
+    pub async fn create(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!("INSERT INTO partner_types VALUES ($1)", self.partner_type)
+            .execute(db)
+            .await
+            .map_err(|e| e.into())
+    }
+
+    /// Partners are not deleted together with their type: the foreign key of
+    /// `partners.partner_type` refuses to delete a type in use.
+    pub async fn delete(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!("DELETE FROM partner_types WHERE partner_type = $1", self.partner_type)
+            .execute(db)
+            .await
+            .map_err(|e| match e.into() {
+                crate::Error::ForeignKeyViolation { source, .. } => crate::Error::ForeignKeyViolation {
+                    table: Some("partners".into()),
+                    column: Some("partner_type".into()),
+                    in_use: true,
+                    source,
+                },
+                e => e,
+            })
+    }
+}
+
+pub async fn get(db: &SqlitePool, partner_type: String) -> Result<PartnerType> {
+    query_as!(PartnerType, "SELECT * FROM partner_types WHERE partner_type = $1", partner_type)
+        .fetch_one(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn get_all(db: &SqlitePool) -> Result<Vec<PartnerType>> {
+    query_as!(PartnerType, "SELECT * FROM partner_types ORDER BY partner_type")
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// A dictionary entry in use that is spelled differently from another one
+/// meaning the same type, and the entry its partners should be moved to.
+#[derive(Clone, Debug)]
+pub struct TypeMapping {
+    pub from: String,
+    pub to: Option<PartnerType>,
+    pub partners: i64,
+}
+
+/// Lists the entries in use that duplicate another one, such as the free-text
+/// types the dictionary took over from before it existed, each mapped onto the
+/// entry spelled the normalized way or, failing that, the one used the most.
+pub async fn normalization_preview(db: &SqlitePool) -> Result<Vec<TypeMapping>> {
+    let entries = query!(
+        r#"SELECT partner_types.partner_type, COUNT(partners.id) AS "partners!: i64" FROM partner_types
+        LEFT JOIN partners ON partners.partner_type = partner_types.partner_type
+        GROUP BY partner_types.partner_type
+        ORDER BY partner_types.partner_type"#
+    )
+    .fetch_all(db)
+    .await?;
+
+    let target = |key: &str| {
+        entries
+            .iter()
+            .filter(|entry| normalization_key(&entry.partner_type) == key)
+            .max_by_key(|entry| (entry.partner_type == key, entry.partners))
+            .map(|entry| PartnerType::new(entry.partner_type.clone()))
+    };
+
+    Ok(entries
+        .iter()
+        .filter(|entry| entry.partners > 0)
+        .filter_map(|entry| {
+            let to = target(&normalization_key(&entry.partner_type)).filter(|to| to.partner_type != entry.partner_type)?;
+            Some(TypeMapping { from: entry.partner_type.clone(), to: Some(to), partners: entry.partners })
+        })
+        .collect())
+}
+
+/// Moves the partners to the entries their types are mapped onto and deletes
+/// the entries left unused. Unmapped entries are left as is.
+pub async fn normalize(db: &SqlitePool, mappings: &[TypeMapping]) -> Result<()> {
+    let mut transaction = db.begin().await?;
+
+    for mapping in mappings {
+        if let Some(to) = mapping.to.as_ref().filter(|to| to.partner_type != mapping.from) {
+            query!(
+                "UPDATE partners SET partner_type = $1 WHERE partner_type = $2",
+                to.partner_type,
+                mapping.from
+            )
+            .execute(&mut *transaction)
+            .await?;
+            query!("DELETE FROM partner_types WHERE partner_type = $1", mapping.from)
+                .execute(&mut *transaction)
+                .await?;
+        }
+    }
+
+    transaction.commit().await?;
+
+    Ok(())
+}
+
+/// Upper-cases the value, drops punctuation and spaces and replaces Latin
+/// letters and digits that look like Cyrillic ones ("OOO", "0OO") with the latter.
+fn normalization_key(value: &str) -> String {
+    value
+        .chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_uppercase)
+        .map(|c| match c {
+            'A' => 'А',
+            'B' => 'В',
+            'C' => 'С',
+            'E' => 'Е',
+            'H' => 'Н',
+            'K' => 'К',
+            'M' => 'М',
+            'O' | '0' => 'О',
+            'P' => 'Р',
+            'T' => 'Т',
+            'X' => 'Х',
+            'Y' => 'У',
+            c => c,
+        })
+        .collect()
+}
@@ -0,0 +1,251 @@
+use chrono::Utc;
+use sqlx::{types::Uuid, FromRow, SqlitePool};
+
+use serde::{Deserialize, Serialize};
+
+use super::address::Address;
+use super::repository::{Entity, SortOrder, Value};
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct Partner {
+    /// Generated by the API and CSV import when left out.
+    #[serde(default)]
+    pub id: String,
+    pub partner_type: String,
+    pub partner_name: String,
+    pub director: String,
+    pub email: String,
+    pub phone: String,
+    pub legal_address: String,
+    pub inn: String,
+    pub rating: i64,
+    // Parsed from `legal_address` by the API and the CSV import
+    #[serde(default)]
+    pub postal_index: String,
+    #[serde(default)]
+    pub region: String,
+    #[serde(default)]
+    pub settlement: String,
+    #[serde(default)]
+    pub city: String,
+    #[serde(default)]
+    pub street: String,
+    #[serde(default)]
+    pub building: String,
+}
+
+impl Default for Partner {
+    fn default() -> Self {
+        Self::new(
+            "".into(),
+            "".into(),
+            "".into(),
+            "".into(),
+            "".into(),
+            Address::default(),
+            "".into(),
+            0
+        )
+    }
+}
+
+impl PartialEq for Partner {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Partner {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        partner_type: String,
+        partner_name: String,
+        director: String,
+        email: String,
+        phone: String,
+        address: Address,
+        inn: String,
+        rating: i64,
+    ) -> Self {
+        let mut partner = Partner {
+            id: Uuid::new_v4().into(),
+            partner_type,
+            partner_name,
+            director,
+            email,
+            phone,
+            legal_address: String::new(),
+            inn,
+            rating,
+            postal_index: String::new(),
+            region: String::new(),
+            settlement: String::new(),
+            city: String::new(),
+            street: String::new(),
+            building: String::new(),
+        };
+        partner.set_address(address);
+        partner
+    }
+
+    /// Writes the address both as `legal_address` and as its parts.
+    pub fn set_address(&mut self, address: Address) {
+        self.legal_address = address.to_string();
+        self.postal_index = address.postal_index;
+        self.region = address.region;
+        self.settlement = address.settlement;
+        self.city = address.city;
+        self.street = address.street;
+        self.building = address.building;
+    }
+
+    pub fn address(&self) -> Address {
+        Address {
+            postal_index: self.postal_index.clone(),
+            region: self.region.clone(),
+            settlement: self.settlement.clone(),
+            city: self.city.clone(),
+            street: self.street.clone(),
+            building: self.building.clone(),
+        }
+    }
+
+    /// Sets a new rating and records the change with its justification
+    /// in `partner_rating_history`. The change starts from the rating in the
+    /// database, which may differ from `self.rating` if the partner changed since.
+    pub async fn change_rating(
+        &mut self,
+        db: &SqlitePool,
+        rating: i64,
+        comment: String,
+    ) -> crate::Result<()> {
+        if comment.trim().is_empty() {
+            return Err(crate::Error::validation("partner_rating_history", "comment", crate::Rule::Required));
+        }
+
+        let changed_at = Utc::now();
+        let change_id = Uuid::new_v4().to_string();
+
+        let mut transaction = db.begin().await?;
+
+        let old_rating = sqlx::query_scalar!("SELECT rating FROM partners WHERE id = $1", self.id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .ok_or_else(|| crate::Error::NotFound {
+                table: Some("partners".into()),
+                key: Some(self.id.clone()),
+            })?;
+
+        sqlx::query!("UPDATE partners SET rating = $1 WHERE id = $2",
+            rating,
+            self.id )
+            .execute(&mut *transaction)
+            .await?;
+
+        sqlx::query!("INSERT INTO partner_rating_history VALUES ($1,$2,$3,$4,$5,$6)",
+            self.id,
+            changed_at,
+            old_rating,
+            rating,
+            comment,
+            change_id )
+            .execute(&mut *transaction)
+            .await?;
+
+        transaction.commit().await?;
+
+        self.rating = rating;
+        Ok(())
+    }
+}
+
+impl Entity for Partner {
+    const TABLE: &'static str = "partners";
+    const COLUMNS: &'static [&'static str] = &[
+        "id", "partner_type", "partner_name", "director", "email", "phone", "legal_address", "inn",
+        "rating", "postal_index", "region", "settlement", "city", "street", "building",
+    ];
+    const ORDER_BY: &'static [(&'static str, SortOrder)] = &[("partner_name", SortOrder::Asc), ("id", SortOrder::Asc)];
+    const REFERENCES: &'static [(&'static str, &'static str, &'static str)] =
+        &[("partner_type", "partner_types", "partner_type")];
+    /// Changed by [`Partner::change_rating`], which records the change.
+    const KEPT_ON_UPDATE: &'static [&'static str] = &["rating"];
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            self.id.clone().into(),
+            self.partner_type.clone().into(),
+            self.partner_name.clone().into(),
+            self.director.clone().into(),
+            self.email.clone().into(),
+            self.phone.clone().into(),
+            self.legal_address.clone().into(),
+            self.inn.clone().into(),
+            self.rating.into(),
+            self.postal_index.clone().into(),
+            self.region.clone().into(),
+            self.settlement.clone().into(),
+            self.city.clone().into(),
+            self.street.clone().into(),
+            self.building.clone().into(),
+        ]
+    }
+}
+
+pub async fn get_regions(db: &SqlitePool) -> crate::Result<Vec<String>> {
+    sqlx::query_scalar!("SELECT DISTINCT region FROM partners WHERE region <> '' ORDER BY region")
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Cities of the region, or of all regions if `None`.
+pub async fn get_cities(db: &SqlitePool, region: Option<String>) -> crate::Result<Vec<String>> {
+    sqlx::query_scalar!(
+        "SELECT DISTINCT city FROM partners WHERE city <> '' AND ($1 IS NULL OR region = $1) ORDER BY city",
+        region )
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// A partner whose `legal_address` could not be split into parts.
+pub struct UnparsedAddress {
+    pub partner: Partner,
+    pub reason: crate::Error,
+}
+
+/// Splits the `legal_address` of every partner that has no structured address
+/// yet and stores the parts. Returns the addresses that could not be parsed.
+pub async fn parse_addresses(db: &SqlitePool) -> crate::Result<Vec<UnparsedAddress>> {
+    let partners = sqlx::query_as!(Partner, "SELECT * FROM partners WHERE postal_index = ''")
+        .fetch_all(db)
+        .await?;
+
+    let mut unparsed = vec![];
+    let mut transaction = db.begin().await?;
+
+    for partner in partners {
+        match Address::parse(&partner.legal_address) {
+            Ok(address) => {
+                sqlx::query!(
+                    "UPDATE partners SET postal_index = $1, region = $2, settlement = $3, city = $4, street = $5, building = $6
+                    WHERE id = $7",
+                    address.postal_index,
+                    address.region,
+                    address.settlement,
+                    address.city,
+                    address.street,
+                    address.building,
+                    partner.id )
+                    .execute(&mut *transaction)
+                    .await?;
+            }
+            Err(reason) => unparsed.push(UnparsedAddress { partner, reason }),
+        }
+    }
+
+    transaction.commit().await?;
+
+    Ok(unparsed)
+}
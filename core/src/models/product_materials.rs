@@ -0,0 +1,100 @@
+use sqlx::{query, query_as, sqlite::SqliteQueryResult, SqlitePool};
+
+use crate::Result;
+
+/// Bill of materials entry: how much of a material goes into one unit of a product.
+#[derive(Clone, Debug)]
+pub struct ProductMaterial {
+    pub product_id: String,
+    pub material_id: String,
+    pub quantity: f64,
+}
+
+impl ProductMaterial {
+    pub fn new(product_id: String, material_id: String, quantity: f64) -> Self {
+        ProductMaterial {
+            product_id,
+            material_id,
+            quantity,
+        }
+    }
+
+    pub async fn create(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!(
+            "INSERT INTO product_materials VALUES ($1,$2,$3)",
+            self.product_id,
+            self.material_id,
+            self.quantity
+        )
+        .execute(db)
+        .await
+        .map_err(|e| e.into())
+    }
+
+    pub async fn delete(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!(
+            "DELETE FROM product_materials WHERE product_id = $1 AND material_id = $2",
+            self.product_id,
+            self.material_id
+        )
+        .execute(db)
+        .await
+        .map_err(|e| e.into())
+    }
+}
+
+pub async fn get_by_product(db: &SqlitePool, product_id: String) -> Result<Vec<ProductMaterial>> {
+    query_as!(
+        ProductMaterial,
+        "SELECT * FROM product_materials WHERE product_id = $1",
+        product_id
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.into())
+}
+
+pub async fn get_all(db: &SqlitePool) -> Result<Vec<ProductMaterial>> {
+    query_as!(ProductMaterial, "SELECT * FROM product_materials")
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+/// Material cost of a product compared against its minimum cost.
+#[derive(Clone, Debug)]
+pub struct ProductCost {
+    pub product_id: String,
+    pub product_name: String,
+    pub minimum_cost: i64,
+    pub material_cost: f64,
+}
+
+impl ProductCost {
+    /// The product is sold below what its materials cost.
+    pub fn is_below_material_cost(&self) -> bool {
+        (self.minimum_cost as f64) < self.material_cost
+    }
+}
+
+/// Material cost of every product, defects included. Products without a
+/// bill of materials have a material cost of zero.
+pub async fn get_costs(db: &SqlitePool) -> Result<Vec<ProductCost>> {
+    query_as!(
+        ProductCost,
+        r#"SELECT
+            products.id AS "product_id!",
+            products.product_name AS "product_name!",
+            products.minimum_cost AS "minimum_cost!",
+            COALESCE(SUM(
+                product_materials.quantity * materials.price * (1.0 + materials.defect_percentage / 100.0)
+            ), 0.0) AS "material_cost!: f64"
+        FROM products
+        LEFT JOIN product_materials ON product_materials.product_id = products.id
+        LEFT JOIN materials ON materials.id = product_materials.material_id
+        GROUP BY products.id"#
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.into())
+}
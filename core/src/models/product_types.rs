@@ -0,0 +1,39 @@
+use sqlx::FromRow;
+
+use serde::{Deserialize, Serialize};
+
+use super::repository::{Entity, SortOrder, Value};
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductType {
+    pub product_type: String,
+    pub coefficient: f64
+}
+
+impl PartialEq for ProductType {
+    fn eq(&self, other: &Self) -> bool {
+        self.product_type == other.product_type
+    }
+}
+
+impl Default for ProductType {
+    fn default() -> Self {
+        ProductType::new("".to_string(), 0.0)
+    }
+}
+
+impl ProductType {
+    pub fn new(product_type: String, coefficient: f64) -> Self {
+        ProductType { product_type, coefficient }
+    }
+}
+
+impl Entity for ProductType {
+    const TABLE: &'static str = "product_types";
+    const COLUMNS: &'static [&'static str] = &["product_type", "coefficient"];
+    const ORDER_BY: &'static [(&'static str, SortOrder)] = &[("product_type", SortOrder::Asc)];
+
+    fn values(&self) -> Vec<Value> {
+        vec![self.product_type.clone().into(), self.coefficient.into()]
+    }
+}
@@ -0,0 +1,81 @@
+use sqlx::{sqlite::SqliteRow, FromRow, Row};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::repository::{Entity, SortOrder, Value};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Product {
+    pub product_type: String,
+    pub product_name: String,
+    pub article_number: String,
+    pub minimum_cost: i64,
+    #[serde(default)]
+    pub id: String
+}
+
+// Older databases hold fractional costs in `minimum_cost`; read them the way
+// `query_as!` always did, truncated, instead of rejecting the row.
+impl FromRow<'_, SqliteRow> for Product {
+    fn from_row(row: &SqliteRow) -> sqlx::Result<Self> {
+        Ok(Product {
+            product_type: row.try_get("product_type")?,
+            product_name: row.try_get("product_name")?,
+            article_number: row.try_get("article_number")?,
+            minimum_cost: row.try_get_unchecked("minimum_cost")?,
+            id: row.try_get("id")?,
+        })
+    }
+}
+
+impl Default for Product {
+    fn default() -> Self {
+        Product::new(
+            "".to_string(),
+            "".to_string(),
+            "".to_string(),
+            0
+        )
+    }
+}
+
+impl PartialEq for Product {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Product {
+    pub fn new(
+        product_type: String,
+        product_name: String,
+        article_number: String,
+        minimum_cost: i64,
+    ) -> Self {
+        Product {
+            product_type,
+            product_name,
+            article_number,
+            minimum_cost,
+            id: Uuid::new_v4().to_string()
+        }
+    }
+}
+
+impl Entity for Product {
+    const TABLE: &'static str = "products";
+    const COLUMNS: &'static [&'static str] = &["id", "product_type", "product_name", "article_number", "minimum_cost"];
+    const ORDER_BY: &'static [(&'static str, SortOrder)] = &[("product_name", SortOrder::Asc), ("id", SortOrder::Asc)];
+    const REFERENCES: &'static [(&'static str, &'static str, &'static str)] =
+        &[("product_type", "product_types", "product_type")];
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            self.id.clone().into(),
+            self.product_type.clone().into(),
+            self.product_name.clone().into(),
+            self.article_number.clone().into(),
+            self.minimum_cost.into(),
+        ]
+    }
+}
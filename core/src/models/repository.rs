@@ -0,0 +1,439 @@
+//! CRUD shared by every table. A model describes its table by implementing
+//! [`Entity`] and gets the operations of [`Repository`] for free.
+
+use std::fmt;
+use std::future::Future;
+
+use chrono::NaiveDate;
+use sqlx::{
+    encode::IsNull,
+    error::BoxDynError,
+    sqlite::{SqliteArgumentValue, SqliteQueryResult, SqliteRow, SqliteTypeInfo},
+    Encode, FromRow, QueryBuilder, Sqlite, SqliteConnection, SqlitePool, Type,
+};
+
+use crate::{Error, Result};
+
+/// A value of a column, bound as a query parameter.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Text(String),
+    Integer(i64),
+    Real(f64),
+    Date(NaiveDate),
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Self {
+        Value::Text(value)
+    }
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Self {
+        Value::Text(value.to_string())
+    }
+}
+
+impl From<i64> for Value {
+    fn from(value: i64) -> Self {
+        Value::Integer(value)
+    }
+}
+
+impl From<f64> for Value {
+    fn from(value: f64) -> Self {
+        Value::Real(value)
+    }
+}
+
+impl From<NaiveDate> for Value {
+    fn from(value: NaiveDate) -> Self {
+        Value::Date(value)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Value::Null, Into::into)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Null => Ok(()),
+            Value::Text(value) => write!(f, "{}", value),
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Real(value) => write!(f, "{}", value),
+            Value::Date(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl Type<Sqlite> for Value {
+    fn type_info() -> SqliteTypeInfo {
+        <str as Type<Sqlite>>::type_info()
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Value {
+    fn encode_by_ref(&self, buf: &mut Vec<SqliteArgumentValue<'q>>) -> std::result::Result<IsNull, BoxDynError> {
+        match self {
+            Value::Null => Ok(IsNull::Yes),
+            Value::Text(value) => <String as Encode<Sqlite>>::encode_by_ref(value, buf),
+            Value::Integer(value) => <i64 as Encode<Sqlite>>::encode_by_ref(value, buf),
+            Value::Real(value) => <f64 as Encode<Sqlite>>::encode_by_ref(value, buf),
+            Value::Date(value) => <NaiveDate as Encode<Sqlite>>::encode_by_ref(value, buf),
+        }
+    }
+
+    fn produces(&self) -> Option<SqliteTypeInfo> {
+        match self {
+            Value::Null | Value::Text(_) => None,
+            Value::Integer(_) => Some(<i64 as Type<Sqlite>>::type_info()),
+            Value::Real(_) => Some(<f64 as Type<Sqlite>>::type_info()),
+            Value::Date(_) => Some(<NaiveDate as Type<Sqlite>>::type_info()),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ge,
+    Le,
+}
+
+impl Comparison {
+    fn sql(&self) -> &'static str {
+        match self {
+            Comparison::Eq => " = ",
+            Comparison::Ge => " >= ",
+            Comparison::Le => " <= ",
+        }
+    }
+}
+
+/// A condition on a column; [`Repository::list`] returns the records matching all of them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Filter {
+    pub column: &'static str,
+    pub comparison: Comparison,
+    pub value: Value,
+}
+
+impl Filter {
+    pub fn eq(column: &'static str, value: impl Into<Value>) -> Self {
+        Filter { column, comparison: Comparison::Eq, value: value.into() }
+    }
+
+    pub fn ge(column: &'static str, value: impl Into<Value>) -> Self {
+        Filter { column, comparison: Comparison::Ge, value: value.into() }
+    }
+
+    pub fn le(column: &'static str, value: impl Into<Value>) -> Self {
+        Filter { column, comparison: Comparison::Le, value: value.into() }
+    }
+}
+
+/// Which part of a list to return.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pagination {
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SortOrder {
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    fn sql(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => " ASC",
+            SortOrder::Desc => " DESC",
+        }
+    }
+
+    /// Comparison of the records after a value in this order.
+    fn after(&self) -> &'static str {
+        match self {
+            SortOrder::Asc => " > ",
+            SortOrder::Desc => " < ",
+        }
+    }
+}
+
+/// Where a page of [`Repository::page`] starts: the values of the `ORDER_BY`
+/// columns of the last record of the previous page.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Cursor(pub Vec<Value>);
+
+/// A record of a table. `COLUMNS` and [`values`](Entity::values) list the
+/// columns in the same order, starting with the primary key.
+pub trait Entity: for<'r> FromRow<'r, SqliteRow> + Send + Sync + Unpin {
+    const TABLE: &'static str;
+    const COLUMNS: &'static [&'static str];
+    /// Columns [`Repository::list`] sorts by. The last one must be unique, so
+    /// that a [`Cursor`] points at a single record.
+    const ORDER_BY: &'static [(&'static str, SortOrder)];
+    /// Foreign keys as (column, referenced table, referenced column), to name
+    /// the field when a write refers to a missing record.
+    const REFERENCES: &'static [(&'static str, &'static str, &'static str)] = &[];
+    /// Columns [`Repository::update`] leaves as they are, for those changed
+    /// only by operations of their own that keep a history.
+    const KEPT_ON_UPDATE: &'static [&'static str] = &[];
+
+    fn values(&self) -> Vec<Value>;
+
+    fn key(&self) -> Value {
+        self.values().swap_remove(0)
+    }
+
+    /// Cursor of the page following this record.
+    fn cursor(&self) -> Cursor {
+        let values = self.values();
+        Cursor(
+            Self::ORDER_BY
+                .iter()
+                .map(|(column, _)| {
+                    Self::COLUMNS
+                        .iter()
+                        .position(|c| c == column)
+                        .map_or(Value::Null, |i| values[i].clone())
+                })
+                .collect(),
+        )
+    }
+}
+
+/// `ORDER BY` clause of the columns of `ORDER_BY`, qualified by the table so
+/// that it also sorts a query joining others to the table.
+pub fn order_by<T: Entity>() -> String {
+    let columns: Vec<String> = T::ORDER_BY
+        .iter()
+        .map(|(column, order)| [T::TABLE, ".", column, order.sql()].concat())
+        .collect();
+    [" ORDER BY ", &columns.join(", ")].concat()
+}
+
+/// [`Repository::version`] of any table, including those without an
+/// [`Entity`], e.g. `materials`.
+pub async fn version(db: &SqlitePool, table: &str) -> Result<i64> {
+    let version = sqlx::query_scalar("SELECT version FROM table_versions WHERE table_name = $1")
+        .bind(table)
+        .fetch_optional(db)
+        .await?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Pushes the query of [`Repository::page`], for a query joining other
+/// tables to a page of `T` without resorting to offsets.
+pub fn push_page<T: Entity>(builder: &mut QueryBuilder<Sqlite>, filters: &[Filter], after: Option<&Cursor>, limit: i64) {
+    builder.push(format!("SELECT * FROM {}", T::TABLE));
+    push_filters(builder, filters);
+    if let Some(Cursor(values)) = after {
+        // (a > x) OR (a = x AND b > y) OR ..., each column in its own order
+        builder.push(if filters.is_empty() { " WHERE (" } else { " AND (" });
+        for (i, (column, order)) in T::ORDER_BY.iter().enumerate() {
+            builder.push(if i == 0 { "(" } else { " OR (" });
+            for ((equal, _), value) in T::ORDER_BY[..i].iter().zip(values) {
+                builder.push(equal).push(" = ").push_bind(value.clone()).push(" AND ");
+            }
+            builder
+                .push(column)
+                .push(order.after())
+                .push_bind(values.get(i).cloned().unwrap_or(Value::Null))
+                .push(")");
+        }
+        builder.push(")");
+    }
+    builder.push(order_by::<T>()).push(" LIMIT ").push_bind(limit);
+}
+
+fn push_filters(builder: &mut QueryBuilder<Sqlite>, filters: &[Filter]) {
+    for (i, filter) in filters.iter().enumerate() {
+        builder
+            .push(if i == 0 { " WHERE " } else { " AND " })
+            .push(filter.column)
+            .push(filter.comparison.sql())
+            .push_bind(filter.value.clone());
+    }
+}
+
+/// Completes an error of writing `record` with what SQLite leaves out: the
+/// record, and which of its references points to a missing one.
+async fn explain<T: Entity>(db: &mut SqliteConnection, record: &T, error: Error) -> Error {
+    match error {
+        Error::ForeignKeyViolation { source, .. } => {
+            let values = record.values();
+            for (column, table, key) in T::REFERENCES {
+                let Some(value) = T::COLUMNS.iter().position(|c| c == column).map(|i| values[i].clone()) else {
+                    continue;
+                };
+                let mut builder = QueryBuilder::new(format!("SELECT EXISTS(SELECT 1 FROM {} WHERE {} = ", table, key));
+                builder.push_bind(value).push(")");
+                if let Ok(false) = builder.build_query_scalar::<bool>().fetch_one(&mut *db).await {
+                    return Error::ForeignKeyViolation {
+                        table: Some(T::TABLE.into()),
+                        column: Some(column.to_string()),
+                        in_use: false,
+                        source,
+                    };
+                }
+            }
+            Error::ForeignKeyViolation { table: Some(T::TABLE.into()), column: None, in_use: false, source }
+        }
+        Error::NotFound { .. } => not_found::<T>(record.key()),
+        error => error,
+    }
+}
+
+fn not_found<T: Entity>(key: Value) -> Error {
+    Error::NotFound { table: Some(T::TABLE.into()), key: Some(key.to_string()) }
+}
+
+pub trait Repository: Entity {
+    fn insert(&self, db: &SqlitePool) -> impl Future<Output = Result<SqliteQueryResult>> + Send {
+        async move { self.insert_in(&mut *db.acquire().await?).await }
+    }
+
+    /// [`insert`](Repository::insert) on a connection of the caller, such as
+    /// a transaction the record is a part of.
+    fn insert_in(&self, db: &mut SqliteConnection) -> impl Future<Output = Result<SqliteQueryResult>> + Send {
+        async move {
+            let mut builder = QueryBuilder::new(format!(
+                "INSERT INTO {} ({}) VALUES (",
+                Self::TABLE,
+                Self::COLUMNS.join(", ")
+            ));
+            let mut values = builder.separated(", ");
+            for value in self.values() {
+                values.push_bind(value);
+            }
+            builder.push(")");
+
+            match builder.build().execute(&mut *db).await {
+                Ok(result) => Ok(result),
+                Err(e) => Err(explain(db, self, e.into()).await),
+            }
+        }
+    }
+
+    /// Writes every column but `KEPT_ON_UPDATE` of the record with the same
+    /// key. Fails with [`Error::NotFound`] if there is no such record.
+    fn update(&self, db: &SqlitePool) -> impl Future<Output = Result<SqliteQueryResult>> + Send {
+        async move {
+            let mut builder = QueryBuilder::new(format!("UPDATE {} SET ", Self::TABLE));
+            let mut values = self.values().into_iter();
+            let key = values.next().unwrap_or(Value::Null);
+
+            let mut columns = builder.separated(", ");
+            for (column, value) in Self::COLUMNS[1..].iter().zip(values) {
+                if Self::KEPT_ON_UPDATE.contains(column) {
+                    continue;
+                }
+                columns.push(column).push_unseparated(" = ").push_bind_unseparated(value);
+            }
+            builder.push(format!(" WHERE {} = ", Self::COLUMNS[0])).push_bind(key);
+
+            match builder.build().execute(db).await {
+                Ok(result) if result.rows_affected() == 0 => Err(not_found::<Self>(self.key())),
+                Ok(result) => Ok(result),
+                Err(e) => Err(explain(&mut *db.acquire().await?, self, e.into()).await),
+            }
+        }
+    }
+
+    fn delete(&self, db: &SqlitePool) -> impl Future<Output = Result<SqliteQueryResult>> + Send {
+        async move {
+            let mut builder = QueryBuilder::new(format!("DELETE FROM {} WHERE {} = ", Self::TABLE, Self::COLUMNS[0]));
+            builder.push_bind(self.key());
+
+            Ok(builder.build().execute(db).await?)
+        }
+    }
+
+    /// The record with the given primary key.
+    fn fetch(db: &SqlitePool, key: impl Into<Value> + Send) -> impl Future<Output = Result<Self>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            let mut builder = QueryBuilder::new(format!("SELECT * FROM {} WHERE {} = ", Self::TABLE, Self::COLUMNS[0]));
+            let key = key.into();
+            builder.push_bind(key.clone());
+
+            builder.build_query_as().fetch_one(db).await.map_err(|e| match e {
+                sqlx::Error::RowNotFound => not_found::<Self>(key),
+                e => e.into(),
+            })
+        }
+    }
+
+    /// Records matching all `filters`, the whole list unless `pagination` is given.
+    fn list(
+        db: &SqlitePool,
+        filters: &[Filter],
+        pagination: Option<Pagination>,
+    ) -> impl Future<Output = Result<Vec<Self>>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            let mut builder = QueryBuilder::new(format!("SELECT * FROM {}", Self::TABLE));
+            push_filters(&mut builder, filters);
+            builder.push(order_by::<Self>());
+            if let Some(pagination) = pagination {
+                builder
+                    .push(" LIMIT ")
+                    .push_bind(pagination.limit)
+                    .push(" OFFSET ")
+                    .push_bind(pagination.offset);
+            }
+
+            Ok(builder.build_query_as().fetch_all(db).await?)
+        }
+    }
+
+    /// Up to `limit` records matching all `filters` that come after `after`,
+    /// from the start without it. Unlike an offset, the cursor lets SQLite seek
+    /// straight to the page through the index of `ORDER_BY`, however deep it is.
+    fn page(
+        db: &SqlitePool,
+        filters: &[Filter],
+        after: Option<&Cursor>,
+        limit: i64,
+    ) -> impl Future<Output = Result<Vec<Self>>> + Send
+    where
+        Self: Sized,
+    {
+        async move {
+            let mut builder = QueryBuilder::new("");
+            push_page::<Self>(&mut builder, filters, after, limit);
+
+            Ok(builder.build_query_as().fetch_all(db).await?)
+        }
+    }
+
+    /// How many times the table has changed, counted by the triggers of the
+    /// `table_versions` migration. A cached list is stale once this differs.
+    fn version(db: &SqlitePool) -> impl Future<Output = Result<i64>> + Send {
+        version(db, Self::TABLE)
+    }
+
+    fn count(db: &SqlitePool, filters: &[Filter]) -> impl Future<Output = Result<i64>> + Send {
+        async move {
+            let mut builder = QueryBuilder::new(format!("SELECT COUNT(*) FROM {}", Self::TABLE));
+            push_filters(&mut builder, filters);
+
+            Ok(builder.build_query_scalar().fetch_one(db).await?)
+        }
+    }
+}
+
+impl<T: Entity> Repository for T {}
@@ -0,0 +1,189 @@
+use chrono::NaiveDate;
+use sqlx::{query_as, FromRow, QueryBuilder, SqlitePool};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::repository::{self, Cursor, Entity, Filter, SortOrder, Value};
+
+#[derive(Clone, Debug, Serialize, Deserialize, FromRow)]
+pub struct Sale {
+    pub product_id: String,
+    pub quantity: i64,
+    pub sale_date: NaiveDate,
+    pub partner_id: String,
+    #[serde(default)]
+    pub id: String,
+}
+
+impl Sale {
+    pub fn new(product_id: String, quantity: i64, sale_date: NaiveDate, partner_id: String) -> Self {
+        Sale {
+            product_id,
+            quantity,
+            sale_date,
+            partner_id,
+            id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Checks what the database cannot: a sale moves at least one unit.
+    pub fn validate(&self) -> crate::Result<()> {
+        if self.quantity <= 0 {
+            return Err(crate::Error::validation("sales", "quantity", crate::Rule::Positive));
+        }
+        Ok(())
+    }
+}
+
+impl Entity for Sale {
+    const TABLE: &'static str = "sales";
+    const COLUMNS: &'static [&'static str] = &["id", "product_id", "quantity", "sale_date", "partner_id"];
+    const ORDER_BY: &'static [(&'static str, SortOrder)] = &[("sale_date", SortOrder::Desc), ("id", SortOrder::Asc)];
+    const REFERENCES: &'static [(&'static str, &'static str, &'static str)] =
+        &[("product_id", "products", "id"), ("partner_id", "partners", "id")];
+
+    fn values(&self) -> Vec<Value> {
+        vec![
+            self.id.clone().into(),
+            self.product_id.clone().into(),
+            self.quantity.into(),
+            self.sale_date.into(),
+            self.partner_id.clone().into(),
+        ]
+    }
+}
+
+/// Type of the sold product, compared as a column of `sales`.
+const PRODUCT_TYPE: &str = "(SELECT product_type FROM products WHERE products.id = sales.product_id)";
+
+/// Which sales to return, `None` matching any.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SalesFilter {
+    pub partner_id: Option<String>,
+    /// First day of the period, inclusive.
+    pub from: Option<NaiveDate>,
+    /// Last day of the period, inclusive.
+    pub to: Option<NaiveDate>,
+    pub product_id: Option<String>,
+    pub product_type: Option<String>,
+    /// Smallest quantity, inclusive.
+    pub min_quantity: Option<i64>,
+    /// Largest quantity, inclusive.
+    pub max_quantity: Option<i64>,
+}
+
+impl SalesFilter {
+    pub fn filters(&self) -> Vec<Filter> {
+        let mut filters = vec![];
+        if let Some(partner_id) = &self.partner_id {
+            filters.push(Filter::eq("partner_id", partner_id.as_str()));
+        }
+        if let Some(from) = self.from {
+            filters.push(Filter::ge("sale_date", from));
+        }
+        if let Some(to) = self.to {
+            filters.push(Filter::le("sale_date", to));
+        }
+        if let Some(product_id) = &self.product_id {
+            filters.push(Filter::eq("product_id", product_id.as_str()));
+        }
+        if let Some(product_type) = &self.product_type {
+            filters.push(Filter::eq(PRODUCT_TYPE, product_type.as_str()));
+        }
+        if let Some(min_quantity) = self.min_quantity {
+            filters.push(Filter::ge("quantity", min_quantity));
+        }
+        if let Some(max_quantity) = self.max_quantity {
+            filters.push(Filter::le("quantity", max_quantity));
+        }
+        filters
+    }
+}
+
+/// A sale with the names of its product and partner, to show it without
+/// fetching them one by one.
+#[derive(Clone, Debug, FromRow)]
+pub struct SaleDetails {
+    #[sqlx(flatten)]
+    pub sale: Sale,
+    pub product_name: String,
+    pub product_type: String,
+    pub partner_name: String,
+    pub partner_type: String,
+}
+
+/// Up to `limit` sales matching `filters` after `after`, in the order of
+/// `Repository::page`, with their product and partner joined in the same query.
+pub async fn get_all_detailed(
+    db: &SqlitePool,
+    filters: &[Filter],
+    after: Option<&Cursor>,
+    limit: i64,
+) -> crate::Result<Vec<SaleDetails>> {
+    let mut builder = QueryBuilder::new(
+        "SELECT sales.*,
+            products.product_name, products.product_type,
+            partners.partner_name, partners.partner_type
+        FROM (",
+    );
+    repository::push_page::<Sale>(&mut builder, filters, after, limit);
+    builder.push(
+        ") AS sales
+        JOIN products ON products.id = sales.product_id
+        JOIN partners ON partners.id = sales.partner_id",
+    );
+    builder.push(repository::order_by::<Sale>());
+
+    Ok(builder.build_query_as().fetch_all(db).await?)
+}
+
+/// Sales totals of a single partner or product.
+#[derive(Clone, Debug, Serialize)]
+pub struct SalesSummary {
+    pub id: String,
+    pub name: String,
+    pub sales: i64,
+    pub quantity: i64,
+    /// Quantity times the product's minimum cost.
+    pub amount: i64,
+}
+
+pub async fn summary_by_partner(db: &SqlitePool) -> crate::Result<Vec<SalesSummary>> {
+    query_as!(
+        SalesSummary,
+        r#"SELECT
+            partners.id AS "id!",
+            partners.partner_name AS "name!",
+            COUNT(*) AS "sales!: i64",
+            SUM(sales.quantity) AS "quantity!: i64",
+            SUM(sales.quantity * products.minimum_cost) AS "amount!: i64"
+        FROM sales
+        JOIN partners ON partners.id = sales.partner_id
+        JOIN products ON products.id = sales.product_id
+        GROUP BY partners.id
+        ORDER BY 5 DESC"#
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.into())
+}
+
+pub async fn summary_by_product(db: &SqlitePool) -> crate::Result<Vec<SalesSummary>> {
+    query_as!(
+        SalesSummary,
+        r#"SELECT
+            products.id AS "id!",
+            products.product_name AS "name!",
+            COUNT(*) AS "sales!: i64",
+            SUM(sales.quantity) AS "quantity!: i64",
+            SUM(sales.quantity * products.minimum_cost) AS "amount!: i64"
+        FROM sales
+        JOIN products ON products.id = sales.product_id
+        GROUP BY products.id
+        ORDER BY 5 DESC"#
+    )
+    .fetch_all(db)
+    .await
+    .map_err(|e| e.into())
+}
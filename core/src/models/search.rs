@@ -0,0 +1,97 @@
+//! Full-text search over partners, products and their sales, backed by the
+//! FTS5 tables `partners_search` and `products_search`. Triggers keep them in
+//! sync with the records, see the `search` migration.
+
+use chrono::NaiveDate;
+use sqlx::{query_as, FromRow, SqlitePool};
+
+use super::partners::Partner;
+use super::products::Product;
+use crate::Result;
+
+/// Results of each entity returned at most, best first.
+pub const LIMIT: i64 = 20;
+
+/// A sale of a found partner or product, with the names to show it by.
+#[derive(Clone, Debug, FromRow)]
+pub struct FoundSale {
+    pub id: String,
+    pub product_name: String,
+    pub partner_name: String,
+    pub quantity: i64,
+    pub sale_date: NaiveDate,
+}
+
+/// What matched the search, grouped by entity.
+#[derive(Clone, Debug, Default)]
+pub struct SearchResults {
+    pub partners: Vec<Partner>,
+    pub products: Vec<Product>,
+    pub sales: Vec<FoundSale>,
+}
+
+impl SearchResults {
+    pub fn is_empty(&self) -> bool {
+        self.partners.is_empty() && self.products.is_empty() && self.sales.is_empty()
+    }
+}
+
+/// Records containing every word of `text`, each word matching the start of
+/// a word in partner names, directors, INNs, emails, product names or
+/// article numbers. Sales are found by their partner or product.
+pub async fn search(db: &SqlitePool, text: &str) -> Result<SearchResults> {
+    let Some(pattern) = match_pattern(text) else {
+        return Ok(SearchResults::default());
+    };
+
+    let partners = query_as::<_, Partner>(
+        "SELECT partners.* FROM partners_search
+        JOIN partners ON partners.id = partners_search.id
+        WHERE partners_search MATCH $1
+        ORDER BY partners_search.rank
+        LIMIT $2",
+    )
+    .bind(&pattern)
+    .bind(LIMIT)
+    .fetch_all(db)
+    .await?;
+
+    let products = query_as::<_, Product>(
+        "SELECT products.* FROM products_search
+        JOIN products ON products.id = products_search.id
+        WHERE products_search MATCH $1
+        ORDER BY products_search.rank
+        LIMIT $2",
+    )
+    .bind(&pattern)
+    .bind(LIMIT)
+    .fetch_all(db)
+    .await?;
+
+    let sales = query_as::<_, FoundSale>(
+        "SELECT sales.id, products.product_name, partners.partner_name, sales.quantity, sales.sale_date
+        FROM sales
+        JOIN products ON products.id = sales.product_id
+        JOIN partners ON partners.id = sales.partner_id
+        WHERE sales.partner_id IN (SELECT id FROM partners_search WHERE partners_search MATCH $1)
+            OR sales.product_id IN (SELECT id FROM products_search WHERE products_search MATCH $1)
+        ORDER BY sales.sale_date DESC
+        LIMIT $2",
+    )
+    .bind(&pattern)
+    .bind(LIMIT)
+    .fetch_all(db)
+    .await?;
+
+    Ok(SearchResults { partners, products, sales })
+}
+
+/// FTS5 query of the words of `text` as quoted prefixes, so that the
+/// syntax characters users type, e.g. in emails, are taken literally.
+fn match_pattern(text: &str) -> Option<String> {
+    let words: Vec<String> = text
+        .split_whitespace()
+        .map(|word| ["\"", &word.replace('"', "\"\""), "\"*"].concat())
+        .collect();
+    (!words.is_empty()).then(|| words.join(" "))
+}
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+use sqlx::{query, query_as, sqlite::SqliteQueryResult, SqlitePool};
+use uuid::Uuid;
+
+use crate::Result;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Supplier {
+    pub supplier_type: String,
+    pub supplier_name: String,
+    pub inn: String,
+    pub id: String,
+}
+
+impl Default for Supplier {
+    fn default() -> Self {
+        Supplier::new("".to_string(), "".to_string(), "".to_string())
+    }
+}
+
+impl PartialEq for Supplier {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Supplier {
+    pub fn new(supplier_type: String, supplier_name: String, inn: String) -> Self {
+        Supplier {
+            supplier_type,
+            supplier_name,
+            inn,
+            id: Uuid::new_v4().to_string(),
+        }
+    }
+
+    pub async fn create(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!(
+            "INSERT INTO suppliers VALUES ($1,$2,$3,$4)",
+            self.supplier_type,
+            self.supplier_name,
+            self.inn,
+            self.id
+        )
+        .execute(db)
+        .await
+        .map_err(|e| e.into())
+    }
+
+    pub async fn delete(&self, db: &SqlitePool) -> Result<SqliteQueryResult> {
+        query!("DELETE FROM suppliers WHERE id = $1", self.id)
+            .execute(db)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+pub async fn get(db: &SqlitePool, id: String) -> Result<Supplier> {
+    query_as!(Supplier, "SELECT * FROM suppliers WHERE id = $1", id)
+        .fetch_one(db)
+        .await
+        .map_err(|e| e.into())
+}
+
+pub async fn get_all(db: &SqlitePool) -> Result<Vec<Supplier>> {
+    query_as!(Supplier, "SELECT * FROM suppliers")
+        .fetch_all(db)
+        .await
+        .map_err(|e| e.into())
+}
@@ -0,0 +1,285 @@
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+use printpdf::{
+    IndirectFontRef, Line, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Point,
+};
+use sqlx::SqlitePool;
+
+use crate::models::discounts;
+use crate::models::documents::{self, DocumentType};
+use crate::models::partners::Partner;
+use crate::models::products::Product;
+use crate::models::repository::Repository;
+use crate::models::sales::Sale;
+use crate::Error;
+
+const FONT_REGULAR: &[u8] = include_bytes!("resources/fonts/DejaVuSans.ttf");
+const FONT_BOLD: &[u8] = include_bytes!("resources/fonts/DejaVuSans-Bold.ttf");
+
+const SELLER_NAME: &str = "ООО «Мастер пол»";
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 20.0;
+
+/// Issues (or reissues) a document for the sale and writes it as PDF into `dir`.
+pub async fn save(
+    db: &SqlitePool,
+    sale: &Sale,
+    document_type: DocumentType,
+    dir: &Path,
+) -> crate::Result<PathBuf> {
+    let partner = Partner::fetch(db, sale.partner_id.as_str()).await?;
+    let product = Product::fetch(db, sale.product_id.as_str()).await?;
+
+    let document = documents::get_or_issue(db, document_type, sale.id.clone(), chrono::Local::now().date_naive()).await?;
+
+    let bytes = render(
+        document_type,
+        document.number,
+        document.issue_date,
+        sale,
+        &partner,
+        &product,
+        document.discount,
+    )?;
+
+    std::fs::create_dir_all(dir).map_err(|e| Error::Any(e.to_string()))?;
+    let path = dir.join(format!(
+        "{} № {} от {}.pdf",
+        document_type,
+        document.number,
+        format_date(document.issue_date)
+    ));
+    std::fs::write(&path, bytes).map_err(|e| Error::Any(e.to_string()))?;
+
+    Ok(path)
+}
+
+/// Amount of the sale at the product's minimum cost and the total less the
+/// `discount` in percent. In kopecks, so that a discount of a few rubles is
+/// not rounded away.
+pub fn amounts(sale: &Sale, product: &Product, discount: i64) -> (i64, i64) {
+    let amount = sale.quantity * product.minimum_cost * 100;
+    (amount, amount * (100 - discount) / 100)
+}
+
+/// Renders a single-page invoice or delivery note for the sale. The product
+/// is priced at its minimum cost less the partner's `discount` in percent,
+/// the one the document was issued with.
+pub fn render(
+    document_type: DocumentType,
+    number: i64,
+    issue_date: NaiveDate,
+    sale: &Sale,
+    partner: &Partner,
+    product: &Product,
+    discount: i64,
+) -> crate::Result<Vec<u8>> {
+    let title = format!(
+        "{} № {} от {}",
+        document_type,
+        number,
+        format_date(issue_date)
+    );
+
+    let (doc, page, layer) = PdfDocument::new(&title, Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Слой 1");
+    let mut page = Page {
+        layer: doc.get_page(page).get_layer(layer),
+        regular: add_font(&doc, FONT_REGULAR)?,
+        bold: add_font(&doc, FONT_BOLD)?,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    page.text(&title, 16.0, MARGIN, true);
+    page.skip(4.0);
+
+    page.text(&format!("Поставщик: {}", SELLER_NAME), 10.0, MARGIN, false);
+    page.skip(2.0);
+
+    let counterpart = match document_type {
+        DocumentType::Invoice => "Покупатель",
+        DocumentType::DeliveryNote => "Грузополучатель",
+    };
+    page.text(
+        &format!(
+            "{}: {} «{}», ИНН {}",
+            counterpart, partner.partner_type, partner.partner_name, partner.inn
+        ),
+        10.0,
+        MARGIN,
+        false,
+    );
+    page.text(
+        &format!("Юридический адрес: {}", partner.legal_address),
+        10.0,
+        MARGIN,
+        false,
+    );
+    page.text(&format!("Директор: {}", partner.director), 10.0, MARGIN, false);
+    page.text(&format!("Дата продажи: {}", format_date(sale.sale_date)), 10.0, MARGIN, false);
+    page.skip(6.0);
+
+    let (amount, total) = amounts(sale, product, discount);
+    let columns = [MARGIN, MARGIN + 10.0, MARGIN + 85.0, MARGIN + 115.0, MARGIN + 135.0, MARGIN + 152.0];
+
+    page.rule();
+    page.row(&columns, ["№", "Товар", "Артикул", "Кол-во", "Цена", "Сумма"], true);
+    page.rule();
+
+    // Product names can be long, split them into lines that fit the column.
+    let name_lines = wrap(&product.product_name, 34);
+    let quantity = sale.quantity.to_string();
+    let price = format_money(product.minimum_cost * 100);
+    let sum = format_money(amount);
+    for (i, line) in name_lines.iter().enumerate() {
+        if i == 0 {
+            page.row(
+                &columns,
+                ["1", line, &product.article_number, &quantity, &price, &sum],
+                false,
+            );
+        } else {
+            page.row(&columns, ["", line, "", "", "", ""], false);
+        }
+    }
+    page.rule();
+    page.skip(2.0);
+
+    if discount > 0 {
+        page.text(
+            &format!("Скидка {}%: {}", discount, format_money(amount - total)),
+            10.0,
+            MARGIN + 120.0,
+            false,
+        );
+    }
+    page.text(&format!("Итого: {}", format_money(total)), 11.0, MARGIN + 120.0, true);
+    page.text("Без налога (НДС)", 10.0, MARGIN + 120.0, false);
+    page.skip(4.0);
+    page.text(
+        &format!("Всего наименований 1, на сумму {} руб.", format_money(total)),
+        10.0,
+        MARGIN,
+        false,
+    );
+    page.text(
+        "Цена — минимальная стоимость продукции. Скидка партнера зависит от общего количества",
+        8.0,
+        MARGIN,
+        false,
+    );
+    let steps: Vec<String> = discounts::DISCOUNT_STEPS
+        .iter()
+        .map(|(from, discount)| format!("от {} — {}%", format_quantity(*from), discount))
+        .collect();
+    page.text(
+        &format!("купленной им продукции: {}.", steps.join(", ")),
+        8.0,
+        MARGIN,
+        false,
+    );
+    page.skip(10.0);
+
+    match document_type {
+        DocumentType::Invoice => {
+            page.text("Руководитель ____________________", 10.0, MARGIN, false);
+            page.skip(6.0);
+            page.text("Бухгалтер ____________________", 10.0, MARGIN, false);
+        }
+        DocumentType::DeliveryNote => {
+            page.text("Отпуск груза произвёл ____________________", 10.0, MARGIN, false);
+            page.skip(6.0);
+            page.text("Груз получил ____________________", 10.0, MARGIN, false);
+        }
+    }
+
+    doc.save_to_bytes().map_err(|e| Error::Any(e.to_string()))
+}
+
+fn add_font(doc: &PdfDocumentReference, bytes: &[u8]) -> crate::Result<IndirectFontRef> {
+    doc.add_external_font(Cursor::new(bytes))
+        .map_err(|e| Error::Any(e.to_string()))
+}
+
+/// Writes the document top to bottom, keeping track of the current line.
+struct Page {
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+}
+
+impl Page {
+    fn text(&mut self, text: &str, size: f32, x: f32, bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer.use_text(text, size, Mm(x), Mm(self.y), font);
+        self.y -= size * 0.5;
+    }
+
+    fn row(&mut self, columns: &[f32; 6], cells: [&str; 6], bold: bool) {
+        let font = if bold { &self.bold } else { &self.regular };
+        columns.iter().zip(cells).for_each(|(x, cell)| {
+            self.layer.use_text(cell, 9.0, Mm(*x), Mm(self.y), font);
+        });
+        self.y -= 5.0;
+    }
+
+    /// Horizontal line between the previous and the next row of the table.
+    fn rule(&mut self) {
+        // Rows are positioned by their baseline, leave room for the descenders.
+        let y = self.y + 3.0;
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(MARGIN), Mm(y)), false),
+                (Point::new(Mm(PAGE_WIDTH - MARGIN), Mm(y)), false),
+            ],
+            is_closed: false,
+        });
+        self.y -= 2.0;
+    }
+
+    fn skip(&mut self, height: f32) {
+        self.y -= height;
+    }
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    text.split_whitespace().for_each(|word| {
+        let line = lines.last_mut().unwrap();
+        if !line.is_empty() && line.chars().count() + word.chars().count() + 1 > width {
+            lines.push(word.to_string());
+        } else {
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+    });
+    lines
+}
+
+fn format_date(date: NaiveDate) -> String {
+    format!("{:02}.{:02}.{}", date.day(), date.month(), date.year())
+}
+
+/// A quantity with its thousands apart, e.g. "10 000".
+fn format_quantity(quantity: i64) -> String {
+    let digits = quantity.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(' ');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// Rubles and kopecks of an amount in kopecks.
+fn format_money(kopecks: i64) -> String {
+    format!("{},{:02}", kopecks / 100, kopecks % 100)
+}
@@ -0,0 +1,28 @@
+//! Helpers for the tests of this crate and of the applications linking it,
+//! behind the `test-util` feature.
+
+use chrono::{Datelike, NaiveDate};
+use proptest::strategy::Strategy;
+use sqlx::{sqlite::SqlitePoolOptions, SqlitePool};
+
+/// A fresh in-memory database with the schema of the migrations. A single
+/// connection that is never recycled, since every connection to
+/// `sqlite::memory:` is a database of its own.
+pub async fn connect_in_memory() -> crate::Result<SqlitePool> {
+    let db = SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await?;
+
+    crate::MIGRATOR.run(&db).await.map_err(sqlx::Error::from)?;
+
+    Ok(db)
+}
+
+/// Any day between the first and the last `NaiveDate`, BCE included.
+pub fn any_date() -> impl Strategy<Value = NaiveDate> {
+    (NaiveDate::MIN.num_days_from_ce()..=NaiveDate::MAX.num_days_from_ce())
+        .prop_map(|days| NaiveDate::from_num_days_from_ce_opt(days).unwrap())
+}
@@ -0,0 +1,39 @@
+-- Records every test starts with: two partners buying two products, one of
+-- them made of a supplied material, with a printed sale and a rating change.
+
+INSERT INTO product_types (product_type, coefficient) VALUES
+    ('Ламинат', 2.35),
+    ('Паркетная доска', 4.34);
+
+INSERT INTO products (product_type, product_name, article_number, minimum_cost, id) VALUES
+    ('Ламинат', 'Ламинат Дуб дымчато-белый 33 класс 12 мм', '7750282', 1799, 'product-1'),
+    ('Паркетная доска', 'Паркетная доска Ясень темный однополосная 14 мм', '8758385', 4456, 'product-2');
+
+INSERT INTO partners (partner_type, partner_name, director, email, phone, legal_address, inn, rating, id,
+    postal_index, region, settlement, city, street, building) VALUES
+    ('ЗАО', 'База Строитель', 'Иванова Александра Ивановна', 'aleksandraivanova@ml.ru', '493 123 45 67',
+        '652050, Кемеровская область, город Юрга, ул. Лесная, 15', '2222455179', 7, 'partner-1',
+        '652050', 'Кемеровская область', 'город', 'Юрга', 'ул. Лесная', '15'),
+    ('ООО', 'Паркет 29', 'Петров Василий Петрович', 'vppetrov@vl.ru', '987 123 56 78',
+        '164500, Архангельская область, город Северодвинск, ул. Строителей, 18', '3333888520', 7, 'partner-2',
+        '164500', 'Архангельская область', 'город', 'Северодвинск', 'ул. Строителей', '18');
+
+INSERT INTO sales (product_id, quantity, sale_date, partner_id, id) VALUES
+    ('product-1', 15500, '2023-03-23', 'partner-1', 'sale-1'),
+    ('product-2', 12350, '2023-12-18', 'partner-1', 'sale-2'),
+    ('product-1', 5000, '2024-01-10', 'partner-2', 'sale-3');
+
+INSERT INTO suppliers (supplier_type, supplier_name, inn, id) VALUES
+    ('ООО', 'Древесные плиты', '5432109876', 'supplier-1');
+
+INSERT INTO materials (material_name, unit, price, defect_percentage, supplier_id, id) VALUES
+    ('ДСП 16 мм', 'м²', 420.0, 2.5, 'supplier-1', 'material-1');
+
+INSERT INTO product_materials (product_id, material_id, quantity) VALUES
+    ('product-1', 'material-1', 0.5);
+
+INSERT INTO documents (document_type, number, issue_date, sale_id, id) VALUES
+    ('invoice', 1, '2023-03-24', 'sale-1', 'document-1');
+
+INSERT INTO partner_rating_history (partner_id, changed_at, old_rating, new_rating, comment, id) VALUES
+    ('partner-1', '2024-02-01T10:00:00Z', 5, 7, 'Объем закупок вырос', 'rating-change-1');
@@ -0,0 +1,727 @@
+use chrono::{Datelike, NaiveDate};
+use demo_core::models::documents::{self, DocumentType};
+use demo_core::models::materials::{self, Material};
+use demo_core::models::partner_rating_history;
+use demo_core::models::partner_types::{self, PartnerType};
+use demo_core::models::partners::Partner;
+use demo_core::models::product_materials::{self, ProductMaterial};
+use demo_core::models::product_types::ProductType;
+use demo_core::models::products::Product;
+use demo_core::models::repository::{self, Entity, Filter, Repository};
+use demo_core::models::sales::{Sale, SalesFilter};
+use demo_core::models::suppliers::{self, Supplier};
+use demo_core::models::{address::Address, discounts, search};
+use demo_core::test_util::{any_date, connect_in_memory};
+use demo_core::models::duplicates::{self, DuplicateReason, PartnerField};
+use demo_core::{printing, Error, Rule};
+use futures::executor::block_on;
+use proptest::prelude::*;
+use sqlx::SqlitePool;
+
+/// A fresh in-memory database with the records of `fixtures.sql`.
+async fn db() -> SqlitePool {
+    let db = connect_in_memory().await.unwrap();
+    sqlx::raw_sql(include_str!("fixtures.sql")).execute(&db).await.unwrap();
+
+    db
+}
+
+async fn count(db: &SqlitePool, table: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+        .fetch_one(db)
+        .await
+        .unwrap()
+}
+
+/// Rows of the full-text index `table` for the record `id`.
+async fn indexed(db: &SqlitePool, table: &str, id: &str) -> i64 {
+    sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {} WHERE id = $1", table))
+        .bind(id)
+        .fetch_one(db)
+        .await
+        .unwrap()
+}
+
+fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, month, day).unwrap()
+}
+
+/// A day of a four-digit year. SQLite compares dates as text, which follows
+/// the calendar only for them.
+fn four_digit_year_date() -> impl Strategy<Value = NaiveDate> {
+    (date(0, 1, 1).num_days_from_ce()..=date(9999, 12, 31).num_days_from_ce())
+        .prop_map(|days| NaiveDate::from_num_days_from_ce_opt(days).unwrap())
+}
+
+fn ids<T>(records: &[T], id: impl Fn(&T) -> &str) -> Vec<String> {
+    records.iter().map(|record| id(record).to_string()).collect()
+}
+
+fn partner() -> Partner {
+    Partner::new(
+        "ООО".into(),
+        "Стройсервис".into(),
+        "Соловьев Андрей Николаевич".into(),
+        "ansolovev@st.ru".into(),
+        "812 223 32 00".into(),
+        Address::parse("188910, Ленинградская область, город Приморск, ул. Парковая, 21").unwrap(),
+        "4440391029".into(),
+        7,
+    )
+}
+
+#[test]
+fn addresses_keep_how_the_city_is_written() {
+    for written in [
+        "652050, Кемеровская область, город Юрга, ул. Лесная, 15",
+        "652050, Кемеровская область, г. Юрга, ул. Лесная, 15",
+        "652050, Кемеровская область, Юрга, ул. Лесная, 15",
+        "188910, Ленинградская область, пос. Лесной, ул. Парковая, 21",
+    ] {
+        assert_eq!(Address::parse(written).unwrap().to_string(), written);
+    }
+    assert_eq!(Address::parse("652050, Кемеровская область, г.Юрга, ул. Лесная, 15").unwrap().city, "Юрга");
+    assert_eq!(Address::parse("606500, Нижегородская область, город Городец, ул. Ленина, 1").unwrap().city, "Городец");
+
+    let mut address = Address::parse("652050, Кемеровская область, Юрга, ул. Лесная, 15").unwrap();
+    assert_eq!((address.settlement.as_str(), address.city.as_str()), ("", "Юрга"));
+
+    address.postal_index = "65205".into();
+    assert!(matches!(
+        address.validate(),
+        Err(Error::Validation { column, rule: Rule::PostalIndex { index }, .. }) if column == "postal_index" && index == "65205"
+    ));
+    address.postal_index = "652050".into();
+    address.street = " ".into();
+    assert!(matches!(
+        address.validate(),
+        Err(Error::Validation { column, rule: Rule::Required, .. }) if column == "street"
+    ));
+    assert!(matches!(
+        Address::parse("652050, Кемеровская область, город Юрга"),
+        Err(Error::Validation { rule: Rule::AddressParts { found: 3 }, .. })
+    ));
+}
+
+#[async_std::test]
+async fn stored_addresses_keep_how_the_city_is_written() {
+    let db = db().await;
+
+    for written in [
+        "652050, Кемеровская область, г. Юрга, ул. Лесная, 15",
+        "188910, Ленинградская область, Приморск, ул. Парковая, 21",
+    ] {
+        let mut partner = partner();
+        partner.set_address(Address::parse(written).unwrap());
+        partner.insert(&db).await.unwrap();
+
+        let fetched = Partner::fetch(&db, partner.id.as_str()).await.unwrap();
+        assert_eq!(fetched.legal_address, written);
+        assert_eq!(fetched.address().to_string(), written);
+    }
+}
+
+#[async_std::test]
+async fn partners_are_inserted_updated_and_deleted() {
+    let db = db().await;
+
+    let mut partner = partner();
+    partner.insert(&db).await.unwrap();
+    let fetched = Partner::fetch(&db, partner.id.as_str()).await.unwrap();
+    assert_eq!(fetched.partner_name, "Стройсервис");
+    assert_eq!(fetched.city, "Приморск");
+
+    partner.director = "Соловьева Анна Николаевна".into();
+    partner.rating = 9;
+    partner.update(&db).await.unwrap();
+    let fetched = Partner::fetch(&db, partner.id.as_str()).await.unwrap();
+    assert_eq!(fetched.director, "Соловьева Анна Николаевна");
+    assert_eq!(fetched.rating, 7, "the rating changes only with its history");
+
+    let partners = Partner::list(&db, &[], None).await.unwrap();
+    assert_eq!(ids(&partners, |p| &p.id), ["partner-1", "partner-2", partner.id.as_str()]);
+
+    partner.delete(&db).await.unwrap();
+    assert!(matches!(
+        Partner::fetch(&db, partner.id.as_str()).await,
+        Err(Error::NotFound { table: Some(table), .. }) if table == "partners"
+    ));
+    assert!(matches!(partner.update(&db).await, Err(Error::NotFound { .. })));
+}
+
+#[async_std::test]
+async fn products_and_their_types_are_inserted_updated_and_deleted() {
+    let db = db().await;
+
+    let product_type = ProductType::new("Пробковое покрытие".into(), 1.5);
+    product_type.insert(&db).await.unwrap();
+    let mut product = Product::new(product_type.product_type.clone(), "Пробка 10 мм".into(), "5012543".into(), 3000);
+    product.insert(&db).await.unwrap();
+
+    product.minimum_cost = 3200;
+    product.update(&db).await.unwrap();
+    assert_eq!(Product::fetch(&db, product.id.as_str()).await.unwrap().minimum_cost, 3200);
+    assert_eq!(
+        ProductType::fetch(&db, "Пробковое покрытие").await.unwrap().coefficient,
+        1.5
+    );
+
+    let products = Product::list(&db, &[Filter::eq("product_type", "Пробковое покрытие")], None).await.unwrap();
+    assert_eq!(ids(&products, |p| &p.id), [product.id.as_str()]);
+    assert_eq!(ProductType::list(&db, &[], None).await.unwrap().len(), 3);
+
+    product.delete(&db).await.unwrap();
+    product_type.delete(&db).await.unwrap();
+    assert!(Product::fetch(&db, product.id.as_str()).await.is_err());
+    assert!(ProductType::fetch(&db, "Пробковое покрытие").await.is_err());
+}
+
+/// A second record of «Паркет 29», with the same INN and a rating of 3.
+fn parket_duplicate() -> Partner {
+    Partner::new(
+        "ООО".into(),
+        "Паркет-29".into(),
+        "Петров Василий Петрович".into(),
+        "info@parket29.ru".into(),
+        "987 123 56 78".into(),
+        Address::parse("164500, Архангельская область, город Северодвинск, ул. Строителей, 18").unwrap(),
+        "3333 888 520".into(),
+        3,
+    )
+}
+
+#[async_std::test]
+async fn duplicates_are_found_by_inn_and_name() {
+    let db = db().await;
+
+    let found = duplicates::find_duplicates(&db, &parket_duplicate()).await.unwrap();
+    let found: Vec<(&str, &[DuplicateReason])> = found.iter().map(|d| (d.partner.id.as_str(), &d.reasons[..])).collect();
+    assert_eq!(found, [("partner-2", &[DuplicateReason::SameInn, DuplicateReason::SimilarName][..])]);
+
+    let all = duplicates::find_all_duplicates(&db).await.unwrap();
+    assert!(all.is_empty());
+}
+
+#[async_std::test]
+async fn merging_moves_sales_and_history_to_the_survivor() {
+    let db = db().await;
+    let mut duplicate = parket_duplicate();
+    duplicate.insert(&db).await.unwrap();
+    let sale = Sale::new("product-2".into(), 100, date(2024, 3, 1), duplicate.id.clone());
+    sale.insert(&db).await.unwrap();
+    duplicate.change_rating(&db, 4, "Первая закупка".into()).await.unwrap();
+
+    // Loaded when the merge dialog opened, rated again while it was open
+    let survivor = Partner::fetch(&db, "partner-2").await.unwrap();
+    Partner::fetch(&db, "partner-2").await.unwrap().change_rating(&db, 9, "Новый договор".into()).await.unwrap();
+
+    let merged = duplicates::merge(&db, &survivor, &duplicate, &[PartnerField::Email]).await.unwrap();
+    assert_eq!((merged.email.as_str(), merged.rating), ("info@parket29.ru", 9));
+    let stored = Partner::fetch(&db, "partner-2").await.unwrap();
+    assert_eq!((stored.email.as_str(), stored.rating), ("info@parket29.ru", 9));
+    assert!(matches!(Partner::fetch(&db, duplicate.id.as_str()).await, Err(Error::NotFound { .. })));
+    assert_eq!(Sale::fetch(&db, sale.id.as_str()).await.unwrap().partner_id, "partner-2");
+    let history = partner_rating_history::get_by_partner(&db, "partner-2".into()).await.unwrap();
+    let mut changes: Vec<(i64, i64)> = history.iter().map(|change| (change.old_rating, change.new_rating)).collect();
+    changes.sort();
+    assert_eq!(changes, [(3, 4), (7, 9)]);
+
+    // Taking the duplicate's rating records the change from the stored one
+    let mut second = parket_duplicate();
+    second.inn = "3333888521".into();
+    second.insert(&db).await.unwrap();
+    duplicates::merge(&db, &survivor, &second, &[PartnerField::Rating]).await.unwrap();
+    assert_eq!(Partner::fetch(&db, "partner-2").await.unwrap().rating, 3);
+    let history = partner_rating_history::get_by_partner(&db, "partner-2".into()).await.unwrap();
+    assert!(history.iter().any(|change| (change.old_rating, change.new_rating) == (9, 3)));
+}
+
+#[async_std::test]
+async fn partners_are_not_merged_with_themselves() {
+    let db = db().await;
+
+    let partner = Partner::fetch(&db, "partner-1").await.unwrap();
+    assert!(matches!(
+        duplicates::merge(&db, &partner, &partner, &[]).await,
+        Err(Error::Validation { rule: Rule::DistinctMerge, .. })
+    ));
+    assert!(Partner::fetch(&db, "partner-1").await.is_ok());
+}
+
+#[async_std::test]
+async fn constraint_errors_keep_the_sqlite_error() {
+    let db = db().await;
+
+    let error = ProductType::new("Ламинат".into(), 2.35).insert(&db).await.unwrap_err();
+    assert!(matches!(
+        &error,
+        Error::UniqueViolation { table, columns, .. } if table == "product_types" && columns == &["product_type"]
+    ));
+    let source = std::error::Error::source(&error).and_then(|source| source.downcast_ref::<sqlx::Error>());
+    assert!(matches!(source, Some(sqlx::Error::Database(e)) if e.code().as_deref() == Some("1555")));
+}
+
+#[async_std::test]
+async fn sales_are_inserted_updated_and_deleted() {
+    let db = db().await;
+
+    let mut sale = Sale::new("product-2".into(), 300, date(2024, 2, 29), "partner-2".into());
+    sale.insert(&db).await.unwrap();
+    assert_eq!(Sale::fetch(&db, sale.id.as_str()).await.unwrap().sale_date, date(2024, 2, 29));
+
+    sale.quantity = 350;
+    sale.update(&db).await.unwrap();
+    assert_eq!(Sale::fetch(&db, sale.id.as_str()).await.unwrap().quantity, 350);
+
+    let sales = Sale::list(&db, &[Filter::eq("partner_id", "partner-2")], None).await.unwrap();
+    assert_eq!(ids(&sales, |s| &s.id), [sale.id.as_str(), "sale-3"]);
+
+    sale.delete(&db).await.unwrap();
+    assert_eq!(Sale::count(&db, &[]).await.unwrap(), 3);
+}
+
+#[async_std::test]
+async fn pages_after_a_cursor_cover_records_with_equal_sort_keys() {
+    let db = db().await;
+    // Seven sales of one day, so the pages of three split them by id only
+    for id in ["sale-d", "sale-a", "sale-g", "sale-c", "sale-f", "sale-b", "sale-e"] {
+        let sale = Sale { id: id.into(), ..Sale::new("product-1".into(), 10, date(2024, 4, 1), "partner-2".into()) };
+        sale.insert(&db).await.unwrap();
+    }
+
+    let mut paged = vec![];
+    let mut after = None;
+    loop {
+        let page = Sale::page(&db, &[], after.as_ref(), 3).await.unwrap();
+        if page.is_empty() {
+            break;
+        }
+        after = page.last().map(Entity::cursor);
+        paged.extend(page);
+    }
+
+    let listed = Sale::list(&db, &[], None).await.unwrap();
+    assert_eq!(
+        ids(&paged, |s| &s.id),
+        ["sale-a", "sale-b", "sale-c", "sale-d", "sale-e", "sale-f", "sale-g", "sale-3", "sale-2", "sale-1"]
+    );
+    assert_eq!(ids(&paged, |s| &s.id), ids(&listed, |s| &s.id));
+
+    let filters = [Filter::eq("partner_id", "partner-2")];
+    let first = Sale::page(&db, &filters, None, 4).await.unwrap();
+    let rest = Sale::page(&db, &filters, first.last().map(Entity::cursor).as_ref(), 4).await.unwrap();
+    assert_eq!(ids(&first, |s| &s.id), ["sale-a", "sale-b", "sale-c", "sale-d"]);
+    assert_eq!(ids(&rest, |s| &s.id), ["sale-e", "sale-f", "sale-g", "sale-3"]);
+}
+
+#[async_std::test]
+async fn sale_of_a_missing_product_names_the_field() {
+    let db = db().await;
+
+    let sale = Sale::new("product-0".into(), 1, date(2024, 1, 1), "partner-1".into());
+    assert!(matches!(
+        sale.insert(&db).await,
+        Err(Error::ForeignKeyViolation { column: Some(column), .. }) if column == "product_id"
+    ));
+}
+
+#[async_std::test]
+async fn suppliers_are_created_and_deleted() {
+    let db = db().await;
+
+    let supplier = Supplier::new("ИП".into(), "Лаки и краски".into(), "7722334455".into());
+    supplier.create(&db).await.unwrap();
+    assert_eq!(suppliers::get(&db, supplier.id.clone()).await.unwrap().supplier_name, "Лаки и краски");
+    assert_eq!(suppliers::get_all(&db).await.unwrap().len(), 2);
+
+    supplier.delete(&db).await.unwrap();
+    assert!(matches!(suppliers::get(&db, supplier.id.clone()).await, Err(Error::NotFound { .. })));
+    assert_eq!(ids(&suppliers::get_all(&db).await.unwrap(), |s| &s.id), ["supplier-1"]);
+}
+
+#[async_std::test]
+async fn materials_are_created_and_deleted() {
+    let db = db().await;
+
+    let material = Material::new("Лак паркетный".into(), "л".into(), 650.0, 1.0, "supplier-1".into());
+    material.create(&db).await.unwrap();
+    let fetched = materials::get(&db, material.id.clone()).await.unwrap();
+    assert_eq!(fetched.material_name, "Лак паркетный");
+    assert_eq!(fetched.effective_price(), 656.5);
+    assert_eq!(materials::get_all(&db).await.unwrap().len(), 2);
+
+    material.delete(&db).await.unwrap();
+    assert!(materials::get(&db, material.id.clone()).await.is_err());
+    assert_eq!(ids(&materials::get_all(&db).await.unwrap(), |m| &m.id), ["material-1"]);
+}
+
+#[async_std::test]
+async fn changes_to_materials_bump_their_versions() {
+    let db = db().await;
+    let versions = || async {
+        let mut versions = vec![];
+        for table in ["suppliers", "materials", "product_materials"] {
+            versions.push(repository::version(&db, table).await.unwrap());
+        }
+        versions
+    };
+    let before = versions().await;
+
+    let material = Material::new("Лак паркетный".into(), "л".into(), 650.0, 1.0, "supplier-1".into());
+    material.create(&db).await.unwrap();
+    ProductMaterial::new("product-2".into(), material.id.clone(), 0.2).create(&db).await.unwrap();
+    let created = versions().await;
+    assert_eq!(created, [before[0], before[1] + 1, before[2] + 1]);
+
+    // Deleting the supplier cascades to its materials and their entries
+    sqlx::query("DELETE FROM suppliers WHERE id = 'supplier-1'").execute(&db).await.unwrap();
+    let deleted = versions().await;
+    assert!(deleted.iter().zip(&created).all(|(deleted, created)| deleted > created));
+}
+
+#[async_std::test]
+async fn product_materials_are_created_and_deleted() {
+    let db = db().await;
+
+    let entry = ProductMaterial::new("product-2".into(), "material-1".into(), 1.25);
+    entry.create(&db).await.unwrap();
+    let bill = product_materials::get_by_product(&db, "product-2".into()).await.unwrap();
+    assert_eq!(bill.len(), 1);
+    assert_eq!(bill[0].quantity, 1.25);
+    assert_eq!(product_materials::get_all(&db).await.unwrap().len(), 2);
+
+    entry.delete(&db).await.unwrap();
+    assert!(product_materials::get_by_product(&db, "product-2".into()).await.unwrap().is_empty());
+    assert_eq!(product_materials::get_all(&db).await.unwrap().len(), 1);
+}
+
+#[async_std::test]
+async fn product_costs_add_up_materials_with_their_defects() {
+    let db = db().await;
+    let cost = |costs: &[product_materials::ProductCost], id: &str| {
+        costs.iter().find(|cost| cost.product_id == id).unwrap().clone()
+    };
+
+    let costs = product_materials::get_costs(&db).await.unwrap();
+    let laminate = cost(&costs, "product-1");
+    assert!((laminate.material_cost - 0.5 * 420.0 * 1.025).abs() < 1e-9);
+    assert!(!laminate.is_below_material_cost());
+    let parquet = cost(&costs, "product-2");
+    assert_eq!((parquet.minimum_cost, parquet.material_cost), (4456, 0.0));
+    assert!(!parquet.is_below_material_cost());
+
+    let veneer = Material::new("Шпон ясеня".into(), "м²".into(), 4000.0, 25.0, "supplier-1".into());
+    veneer.create(&db).await.unwrap();
+    ProductMaterial::new("product-2".into(), veneer.id.clone(), 1.0).create(&db).await.unwrap();
+
+    let parquet = cost(&product_materials::get_costs(&db).await.unwrap(), "product-2");
+    assert_eq!(parquet.material_cost, 5000.0);
+    assert!(parquet.is_below_material_cost());
+}
+
+#[async_std::test]
+async fn partner_types_in_use_are_not_deleted() {
+    let db = db().await;
+
+    let partner_type = PartnerType::new("НКО".into());
+    partner_type.create(&db).await.unwrap();
+    assert_eq!(partner_types::get(&db, "НКО".into()).await.unwrap(), partner_type);
+    assert_eq!(partner_types::get_all(&db).await.unwrap().len(), 7);
+
+    partner_type.delete(&db).await.unwrap();
+    assert!(partner_types::get(&db, "НКО".into()).await.is_err());
+
+    assert!(matches!(
+        PartnerType::new("ЗАО".into()).delete(&db).await,
+        Err(Error::ForeignKeyViolation { table: Some(table), column: Some(column), in_use: true, .. })
+            if table == "partners" && column == "partner_type"
+    ));
+    assert!(partner_types::get(&db, "ЗАО".into()).await.is_ok());
+}
+
+#[async_std::test]
+async fn partner_types_come_from_the_dictionary() {
+    let db = db().await;
+
+    let mut partner = partner();
+    partner.partner_type = "Ooo".into();
+    assert!(matches!(
+        partner.insert(&db).await,
+        Err(Error::ForeignKeyViolation { table: Some(table), column: Some(column), in_use: false, .. })
+            if table == "partners" && column == "partner_type"
+    ));
+
+    PartnerType::new("Ooo".into()).create(&db).await.unwrap();
+    partner.insert(&db).await.unwrap();
+
+    let mappings = partner_types::normalization_preview(&db).await.unwrap();
+    assert_eq!(mappings.len(), 1);
+    assert_eq!(mappings[0].from, "Ooo");
+    assert_eq!(mappings[0].to, Some(PartnerType::new("ООО".into())));
+
+    partner_types::normalize(&db, &mappings).await.unwrap();
+    assert_eq!(Partner::fetch(&db, partner.id.as_str()).await.unwrap().partner_type, "ООО");
+    assert!(partner_types::get(&db, "Ooo".into()).await.is_err());
+    assert!(partner_types::normalization_preview(&db).await.unwrap().is_empty());
+
+    sqlx::query("UPDATE partner_types SET partner_type = 'ЗАО (непубличное)' WHERE partner_type = 'ЗАО'")
+        .execute(&db)
+        .await
+        .unwrap();
+    assert_eq!(Partner::fetch(&db, "partner-1").await.unwrap().partner_type, "ЗАО (непубличное)");
+}
+
+#[async_std::test]
+async fn free_text_partner_types_survive_the_foreign_key() {
+    let db = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let migrations = &demo_core::MIGRATOR.migrations;
+    let foreign_key = migrations.iter().position(|migration| migration.version == 20261019160000).unwrap();
+    let before = sqlx::migrate::Migrator {
+        migrations: migrations[..foreign_key].to_vec().into(),
+        ..sqlx::migrate::Migrator::DEFAULT
+    };
+    before.run(&db).await.unwrap();
+    // As the records were before partners kept the settlement of their city
+    let fixtures = include_str!("fixtures.sql").replace("settlement, ", "").replace("'город', ", "");
+    sqlx::raw_sql(&fixtures).execute(&db).await.unwrap();
+    sqlx::query("UPDATE partners SET partner_type = 'ooo' WHERE id = 'partner-2'")
+        .execute(&db)
+        .await
+        .unwrap();
+
+    demo_core::MIGRATOR.run(&db).await.unwrap();
+
+    assert_eq!(count(&db, "sales").await, 3);
+    assert_eq!(count(&db, "documents").await, 1);
+    assert_eq!(count(&db, "partner_rating_history").await, 1);
+    assert!(partner_types::get(&db, "ooo".into()).await.is_ok());
+    let partner = Partner::fetch(&db, "partner-1").await.unwrap();
+    assert_eq!(partner.address().to_string(), partner.legal_address);
+
+    let mappings = partner_types::normalization_preview(&db).await.unwrap();
+    assert_eq!(mappings[0].to, Some(PartnerType::new("ООО".into())));
+    partner_types::normalize(&db, &mappings).await.unwrap();
+    let found = search::search(&db, "Паркет").await.unwrap();
+    assert_eq!(ids(&found.partners, |partner| &partner.id), ["partner-2"]);
+    assert_eq!(found.partners[0].partner_type, "ООО");
+}
+
+#[async_std::test]
+async fn search_finds_renamed_records_by_their_new_name() {
+    let db = db().await;
+
+    let mut partner = Partner::fetch(&db, "partner-2").await.unwrap();
+    partner.partner_name = "Стройдвор".into();
+    partner.update(&db).await.unwrap();
+    let mut product = Product::fetch(&db, "product-1").await.unwrap();
+    product.product_name = "Пробка Дуб дымчато-белый 10 мм".into();
+    product.update(&db).await.unwrap();
+
+    let found = search::search(&db, "Стройдвор").await.unwrap();
+    assert_eq!(ids(&found.partners, |partner| &partner.id), ["partner-2"]);
+    assert_eq!(ids(&found.sales, |sale| &sale.id), ["sale-3"]);
+    assert!(search::search(&db, "Паркет 29").await.unwrap().partners.is_empty());
+
+    let found = search::search(&db, "Пробка").await.unwrap();
+    assert_eq!(ids(&found.products, |product| &product.id), ["product-1"]);
+    assert!(search::search(&db, "Ламинат").await.unwrap().products.is_empty());
+}
+
+#[async_std::test]
+async fn deleted_records_drop_out_of_search() {
+    let db = db().await;
+    assert_eq!(search::search(&db, "3333888520").await.unwrap().partners.len(), 1);
+    assert_eq!(search::search(&db, "8758385").await.unwrap().products.len(), 1);
+
+    Partner::fetch(&db, "partner-2").await.unwrap().delete(&db).await.unwrap();
+    Product::fetch(&db, "product-2").await.unwrap().delete(&db).await.unwrap();
+
+    assert!(search::search(&db, "3333888520").await.unwrap().is_empty());
+    assert!(search::search(&db, "8758385").await.unwrap().is_empty());
+    assert_eq!(indexed(&db, "partners_search", "partner-2").await, 0);
+    assert_eq!(indexed(&db, "products_search", "product-2").await, 0);
+    assert_eq!(indexed(&db, "partners_search", "partner-1").await, 1);
+}
+
+#[async_std::test]
+async fn rating_changes_are_recorded() {
+    let db = db().await;
+
+    let mut partner = Partner::fetch(&db, "partner-1").await.unwrap();
+    assert!(matches!(
+        partner.change_rating(&db, 3, " ".into()).await,
+        Err(Error::Validation { .. })
+    ));
+    partner.change_rating(&db, 3, "Задержки оплаты".into()).await.unwrap();
+
+    assert_eq!(Partner::fetch(&db, "partner-1").await.unwrap().rating, 3);
+    let history = partner_rating_history::get_by_partner(&db, "partner-1".into()).await.unwrap();
+    let changes: Vec<(i64, i64)> = history.iter().map(|c| (c.old_rating, c.new_rating)).collect();
+    assert_eq!(changes, [(5, 7), (7, 3)]);
+}
+
+#[async_std::test]
+async fn rating_changes_start_from_the_stored_rating() {
+    let db = db().await;
+
+    let mut stale = Partner::fetch(&db, "partner-1").await.unwrap();
+    Partner::fetch(&db, "partner-1").await.unwrap().change_rating(&db, 3, "Задержки оплаты".into()).await.unwrap();
+    stale.change_rating(&db, 9, "Оплата погашена".into()).await.unwrap();
+
+    let history = partner_rating_history::get_by_partner(&db, "partner-1".into()).await.unwrap();
+    let changes: Vec<(i64, i64)> = history.iter().map(|c| (c.old_rating, c.new_rating)).collect();
+    assert_eq!(changes, [(5, 7), (7, 3), (3, 9)]);
+
+    let mut missing = partner();
+    assert!(matches!(
+        missing.change_rating(&db, 1, "Нет такого партнера".into()).await,
+        Err(Error::NotFound { table: Some(table), .. }) if table == "partners"
+    ));
+    assert_eq!(count(&db, "partner_rating_history").await, 3);
+}
+
+#[async_std::test]
+async fn documents_are_numbered_per_type() {
+    let db = db().await;
+
+    let issued = documents::get_or_issue(&db, DocumentType::Invoice, "sale-1".into(), date(2024, 5, 1))
+        .await
+        .unwrap();
+    assert_eq!((issued.id.as_str(), issued.number), ("document-1", 1));
+
+    let invoice = documents::get_or_issue(&db, DocumentType::Invoice, "sale-2".into(), date(2024, 5, 1))
+        .await
+        .unwrap();
+    assert_eq!(invoice.number, 2);
+    let note = documents::get_or_issue(&db, DocumentType::DeliveryNote, "sale-2".into(), date(2024, 5, 1))
+        .await
+        .unwrap();
+    assert_eq!(note.number, 1);
+}
+
+#[async_std::test]
+async fn reprints_keep_the_discount_they_were_issued_with() {
+    let db = db().await;
+    let dir = std::env::temp_dir().join(format!("demo-core-{}", uuid::Uuid::new_v4()));
+    let sale = Sale::fetch(&db, "sale-2").await.unwrap();
+    let product = Product::fetch(&db, "product-2").await.unwrap();
+    // Sold after the sale, before it is first printed
+    Sale::new("product-1".into(), 30_000, date(2024, 6, 1), "partner-1".into()).insert(&db).await.unwrap();
+
+    let first = printing::save(&db, &sale, DocumentType::Invoice, &dir).await.unwrap();
+    let issued = documents::get_or_issue(&db, DocumentType::Invoice, sale.id.clone(), date(2024, 6, 2)).await.unwrap();
+    assert_eq!(issued.discount, 5);
+    let totals = printing::amounts(&sale, &product, issued.discount);
+
+    Sale::new("product-1".into(), 300_000, date(2024, 7, 1), "partner-1".into()).insert(&db).await.unwrap();
+    assert_eq!(discounts::get(&db, "partner-1").await.unwrap(), 15);
+    let reprint = printing::save(&db, &sale, DocumentType::Invoice, &dir).await.unwrap();
+    let reissued = documents::get_or_issue(&db, DocumentType::Invoice, sale.id.clone(), date(2024, 7, 2)).await.unwrap();
+    assert_eq!(reprint, first);
+    assert_eq!((reissued.number, reissued.discount), (issued.number, 5));
+    assert_eq!(printing::amounts(&sale, &product, reissued.discount), totals);
+
+    // Issued before discounts were kept, it gets the one of its sale's day
+    let old = documents::get_or_issue(&db, DocumentType::Invoice, "sale-1".into(), date(2024, 7, 2)).await.unwrap();
+    assert_eq!((old.id.as_str(), old.discount), ("document-1", 5));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[async_std::test]
+async fn discounts_follow_the_quantity_sold() {
+    let db = db().await;
+
+    let discounts: Vec<(String, i64, i64)> = discounts::get_all(&db)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|d| (d.partner_id, d.total_quantity, d.discount))
+        .collect();
+    assert_eq!(
+        discounts,
+        [("partner-1".to_string(), 27850, 5), ("partner-2".to_string(), 5000, 0)]
+    );
+    assert_eq!(discounts::get(&db, "partner-1").await.unwrap(), 5);
+    assert_eq!(discounts::get(&db, "partner-0").await.unwrap(), 0);
+}
+
+#[async_std::test]
+async fn deleting_a_partner_deletes_its_sales_and_history() {
+    let db = db().await;
+
+    Partner::fetch(&db, "partner-1").await.unwrap().delete(&db).await.unwrap();
+
+    let sales = Sale::list(&db, &[], None).await.unwrap();
+    assert_eq!(ids(&sales, |s| &s.id), ["sale-3"]);
+    assert_eq!(count(&db, "documents").await, 0);
+    assert_eq!(count(&db, "partner_rating_history").await, 0);
+}
+
+#[async_std::test]
+async fn deleting_a_product_type_deletes_its_products_and_their_sales() {
+    let db = db().await;
+
+    ProductType::fetch(&db, "Ламинат").await.unwrap().delete(&db).await.unwrap();
+
+    assert_eq!(ids(&Product::list(&db, &[], None).await.unwrap(), |p| &p.id), ["product-2"]);
+    assert_eq!(ids(&Sale::list(&db, &[], None).await.unwrap(), |s| &s.id), ["sale-2"]);
+    assert!(product_materials::get_all(&db).await.unwrap().is_empty());
+    assert_eq!(count(&db, "documents").await, 0);
+    assert_eq!(materials::get_all(&db).await.unwrap().len(), 1);
+}
+
+#[async_std::test]
+async fn deleting_a_supplier_deletes_its_materials() {
+    let db = db().await;
+
+    suppliers::get(&db, "supplier-1".into()).await.unwrap().delete(&db).await.unwrap();
+
+    assert!(materials::get_all(&db).await.unwrap().is_empty());
+    assert!(product_materials::get_all(&db).await.unwrap().is_empty());
+    assert_eq!(Product::count(&db, &[]).await.unwrap(), 2);
+}
+
+#[async_std::test]
+async fn deleting_a_sale_deletes_its_documents() {
+    let db = db().await;
+
+    Sale::fetch(&db, "sale-1").await.unwrap().delete(&db).await.unwrap();
+
+    assert_eq!(count(&db, "documents").await, 0);
+    assert_eq!(Partner::count(&db, &[]).await.unwrap(), 2);
+    assert_eq!(Product::count(&db, &[]).await.unwrap(), 2);
+}
+
+#[test]
+fn sale_dates_round_trip_through_the_database() {
+    let db = block_on(db());
+    proptest!(|(day in any_date())| {
+        let sale = Sale::new("product-1".into(), 1, day, "partner-1".into());
+        block_on(sale.insert(&db)).unwrap();
+        prop_assert_eq!(block_on(Sale::fetch(&db, sale.id.as_str())).unwrap().sale_date, day);
+        block_on(sale.delete(&db)).unwrap();
+    });
+}
+
+#[test]
+fn period_filter_finds_the_sales_of_the_day() {
+    let db = block_on(db());
+    proptest!(|(day in four_digit_year_date())| {
+        let sale = Sale::new("product-1".into(), 1, day, "partner-1".into());
+        block_on(sale.insert(&db)).unwrap();
+
+        let filter = SalesFilter { from: Some(day), to: Some(day), ..Default::default() };
+        let found = block_on(Sale::list(&db, &filter.filters(), None)).unwrap();
+        prop_assert!(found.iter().any(|found| found.id == sale.id));
+        prop_assert!(found.iter().all(|found| found.sale_date == day));
+
+        block_on(sale.delete(&db)).unwrap();
+    });
+}
@@ -0,0 +1,235 @@
+## Navigation
+
+main-menu = Main menu
+language = Language
+partners = Partners
+sales = Sales
+goods = Goods
+products = Products
+materials = Materials
+go-back = Back
+go-forward = Forward
+search-hint = Search ({ $shortcut })
+found-sale = { $product } for { $partner }, { $quantity } pcs, { $date }
+
+## Shared buttons and labels
+
+add = Add
+submit = Submit
+submit-anyway = Submit anyway
+delete = Delete
+apply = Apply
+choose = Choose
+all = All
+error = Error
+name = Name
+record-submitted = { $form }: record submitted
+shown-of = Shown { $shown } of { $total }
+cancel = Cancel
+new-record = New record
+confirm-delete = Deletion
+delete-record = Delete "{ $name }"?
+
+## Command palette
+
+command-palette = Command palette
+palette-hint = Action, partner, product or material
+action = Action
+nothing-found = Nothing found
+
+## Notifications
+
+details = Details
+error-details = Error details
+copy = Copy
+sqlite-code = SQLite error code: { $code }
+no-code = none
+
+## Partners
+
+partner = Partner
+partner-types = Organization types
+duplicates = Duplicates
+parse-addresses = Parse addresses
+normalize-types = Normalize types
+addresses-unparsed = Addresses that could not be parsed: { $count }
+organization-name = Organization name
+organization-type = Organization type
+organization-director = Director
+director = Director
+email = E-Mail
+contact-phone = Contact phone
+phone = Phone
+address = Address
+postal-index = Postal code
+region = Region
+city = City
+street = Street
+building = Building
+inn = TIN
+rating = Rating
+possible-duplicates = This partner may already exist:
+possible-duplicate = { $name } | { $kind }, TIN { $inn } ({ $reason })
+partner-created = Partner added
+change-rating = Change rating
+new-rating = New rating
+rating-reason = Reason for the change
+rating-reason-required = Give a reason for the rating change
+rating-changed = Rating changed
+rating-unchanged = The rating has not changed
+rating-history = Change history
+types-match = The list has no duplicate organization types
+value = Value
+partners-count = Partners
+replace-with = Replace with
+keep = Keep
+types-normalized = Organization types normalized
+merge-partners = Merge partners
+no-duplicates = No similar partners found
+duplicate-pair = { $first } and { $second }: { $reason }
+reason-same-inn = same TIN
+reason-similar-name = similar name
+reason-similar-email = similar E-Mail
+survivor = Main record
+duplicate = Duplicate
+merge-note = Sales and rating history of the duplicate move to the main record, the duplicate is deleted.
+merge = Merge
+partners-merged = Partners merged
+address-parsing = Address parsing
+addresses-parsed = All addresses are parsed
+
+## Sales
+
+sale = Sale
+product = Product
+quantity = Quantity
+sale-date = Sale date
+date = Date
+print = Print
+document-invoice = Invoice
+document-delivery-note = Delivery note
+document-saved = Document saved: { $path }
+filters = Filters
+period = Period
+from = from
+to = to
+reset-filters = Reset filters
+no-preset = No preset
+preset-name = Preset name
+save-preset = Save preset
+delete-preset = Delete preset
+
+## Products
+
+new-type = New type
+type = Type
+article-number = Article number
+minimum-cost = Minimum price
+product-type = Product type
+coefficient = Coefficient
+
+## Materials
+
+material = Material
+new-supplier = New supplier
+supplier = Supplier
+suppliers = Suppliers
+product-materials = Product materials
+unit = Unit
+unit-price = Price per unit
+defect-percentage = Defect percentage
+quantity-per-unit = Quantity per product unit
+production-cost = Production cost
+material-cost = Material cost
+below-material-cost = The minimum price is below the material cost
+price = Price
+defect = Defects
+effective-price = Price including defects
+
+## Settings
+
+settings = Settings
+theme = Theme
+theme-light = Light
+theme-dark = Dark
+theme-system = System
+ui-scale = Interface scale
+font-size = Font size
+reset-settings = Reset settings
+saved-session-hint = Open windows, form drafts and navigation history are kept when the application closes.
+discard-session = Discard drafts and history
+session-discarded = Drafts and history discarded
+
+## Errors of the models
+
+error-unique =
+    { $record }: a record with the same { $count ->
+        [one] value of the field
+       *[other] values of the fields
+    } { $fields } already exists
+error-foreign-key-field = { $record }: the field «{ $field }» refers to a missing record
+error-foreign-key-in-use = { $record }: the field «{ $field }» refers to the record being deleted
+error-foreign-key-record = { $record }: refers to a missing record
+error-foreign-key = A related record is missing
+error-not-found-key = { $record }: record «{ $key }» not found
+error-not-found-record = { $record }: record not found
+error-not-found = Record not found
+error-validation = { $record }, field «{ $field }»: { $message }
+rule-required = is required
+rule-address-parts = expected 5 comma-separated parts (postal code, region, city, street, building), found { $found }
+rule-postal-index = postal code «{ $index }» must be 6 digits
+rule-distinct-merge = a partner cannot be merged with itself
+rule-positive = must be greater than zero
+error-database =
+    The query failed:
+    { $error }
+
+record-partners = Partner
+record-partner_types = Organization type
+record-partner_rating_history = Rating change
+record-products = Product
+record-product_types = Product type
+record-product_materials = Product materials
+record-materials = Material
+record-suppliers = Supplier
+record-sales = Sale
+record-documents = Document
+
+field-id = Identifier
+field-partner_name = Organization name
+field-supplier_name = Organization name
+field-partner_type = Organization type
+field-supplier_type = Organization type
+field-product_name = Name
+field-material_name = Name
+field-director = Director
+field-email = E-Mail
+field-phone = Phone
+field-legal_address = Legal address
+field-inn = TIN
+field-rating = Rating
+field-old_rating = Rating
+field-new_rating = Rating
+field-comment = Reason for the change
+field-postal_index = Postal code
+field-region = Region
+field-city = City
+field-street = Street
+field-building = Building
+field-product_type = Product type
+field-coefficient = Coefficient
+field-article_number = Article number
+field-minimum_cost = Minimum price
+field-unit = Unit
+field-price = Price per unit
+field-defect_percentage = Defect percentage
+field-quantity = Quantity
+field-sale_date = Sale date
+field-document_type = Document type
+field-number = Number
+field-issue_date = Issue date
+field-product_id = Product
+field-partner_id = Partner
+field-material_id = Material
+field-supplier_id = Supplier
+field-sale_id = Sale
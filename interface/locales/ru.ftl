@@ -0,0 +1,235 @@
+## Navigation
+
+main-menu = Главное меню
+language = Язык
+partners = Партнеры
+sales = Продажи
+goods = Товары
+products = Продукты
+materials = Материалы
+go-back = Назад
+go-forward = Вперед
+search-hint = Поиск ({ $shortcut })
+found-sale = { $product } для { $partner }, { $quantity } шт., { $date }
+
+## Shared buttons and labels
+
+add = Добавить
+submit = Внести
+submit-anyway = Всё равно внести
+delete = Удалить
+apply = Применить
+choose = Выбрать
+all = Все
+error = Ошибка
+name = Название
+record-submitted = { $form }: запись внесена
+shown-of = Показано { $shown } из { $total }
+cancel = Отмена
+new-record = Новая запись
+confirm-delete = Удаление
+delete-record = Удалить «{ $name }»?
+
+## Command palette
+
+command-palette = Палитра команд
+palette-hint = Действие, партнер, продукт или материал
+action = Действие
+nothing-found = Ничего не найдено
+
+## Notifications
+
+details = Подробнее
+error-details = Подробности ошибки
+copy = Скопировать
+sqlite-code = Код ошибки SQLite: { $code }
+no-code = нет
+
+## Partners
+
+partner = Партнер
+partner-types = Типы организаций
+duplicates = Дубликаты
+parse-addresses = Разобрать адреса
+normalize-types = Нормализация типов
+addresses-unparsed = Не удалось разобрать адресов: { $count }
+organization-name = Название организации
+organization-type = Тип организации
+organization-director = Директор организации
+director = Директор
+email = E-Mail
+contact-phone = Контактный номер
+phone = Телефон
+address = Адрес
+postal-index = Индекс
+region = Регион
+city = Город
+street = Улица
+building = Дом
+inn = ИНН
+rating = Рейтинг
+possible-duplicates = Возможно, этот партнер уже внесен:
+possible-duplicate = { $name } | { $kind }, ИНН { $inn } ({ $reason })
+partner-created = Партнер внесен
+change-rating = Изменить рейтинг
+new-rating = Новый рейтинг
+rating-reason = Причина изменения
+rating-reason-required = Укажите причину изменения рейтинга
+rating-changed = Рейтинг изменен
+rating-unchanged = Рейтинг не изменялся
+rating-history = История изменений
+types-match = В справочнике нет повторяющихся типов организаций
+value = Значение
+partners-count = Партнеров
+replace-with = Заменить на
+keep = Не менять
+types-normalized = Типы организаций нормализованы
+merge-partners = Объединение партнеров
+no-duplicates = Похожих партнеров не найдено
+duplicate-pair = { $first } и { $second }: { $reason }
+reason-same-inn = совпадает ИНН
+reason-similar-name = похожее название
+reason-similar-email = похожий E-Mail
+survivor = Основная запись
+duplicate = Дубликат
+merge-note = Продажи и история рейтинга дубликата перейдут к основной записи, дубликат будет удален.
+merge = Объединить
+partners-merged = Партнеры объединены
+address-parsing = Разбор адресов
+addresses-parsed = Все адреса разобраны
+
+## Sales
+
+sale = Продажа
+product = Продукт
+quantity = Количество
+sale-date = Дата продажи
+date = Дата
+print = Печать
+document-invoice = Счёт
+document-delivery-note = Товарная накладная
+document-saved = Документ сохранен: { $path }
+filters = Фильтры
+period = Период
+from = с
+to = по
+reset-filters = Сбросить фильтры
+no-preset = Без пресета
+preset-name = Название пресета
+save-preset = Сохранить пресет
+delete-preset = Удалить пресет
+
+## Products
+
+new-type = Новый тип
+type = Тип
+article-number = Артикул
+minimum-cost = Минимальная цена
+product-type = Тип продукта
+coefficient = Коэффициент
+
+## Materials
+
+material = Материал
+new-supplier = Новый поставщик
+supplier = Поставщик
+suppliers = Поставщики
+product-materials = Состав продукта
+unit = Единица измерения
+unit-price = Цена за единицу
+defect-percentage = Процент брака
+quantity-per-unit = Количество на единицу продукции
+production-cost = Себестоимость продукции
+material-cost = Стоимость материалов
+below-material-cost = Минимальная цена ниже стоимости материалов
+price = Цена
+defect = Брак
+effective-price = Цена с учетом брака
+
+## Settings
+
+settings = Настройки
+theme = Тема
+theme-light = Светлая
+theme-dark = Тёмная
+theme-system = Как в системе
+ui-scale = Масштаб интерфейса
+font-size = Размер шрифта
+reset-settings = Сбросить настройки
+saved-session-hint = Открытые окна, черновики форм и история переходов сохраняются при закрытии программы.
+discard-session = Сбросить черновики и историю
+session-discarded = Черновики и история сброшены
+
+## Errors of the models
+
+error-unique =
+    { $record }: запись с таким значением { $count ->
+        [one] поля
+       *[other] полей
+    } { $fields } уже существует
+error-foreign-key-field = { $record }: в поле «{ $field }» указана несуществующая запись
+error-foreign-key-in-use = { $record }: в поле «{ $field }» указана удаляемая запись
+error-foreign-key-record = { $record }: указана несуществующая связанная запись
+error-foreign-key = Указана несуществующая связанная запись
+error-not-found-key = { $record }: запись «{ $key }» не найдена
+error-not-found-record = { $record }: запись не найдена
+error-not-found = Запись не найдена
+error-validation = { $record }, поле «{ $field }»: { $message }
+rule-required = обязательно для заполнения
+rule-address-parts = ожидается 5 частей через запятую (индекс, регион, город, улица, дом), найдено { $found }
+rule-postal-index = индекс «{ $index }» должен состоять из 6 цифр
+rule-distinct-merge = нельзя объединить партнера с самим собой
+rule-positive = должно быть больше нуля
+error-database =
+    Ошибка при выполнении запроса:
+    { $error }
+
+record-partners = Партнер
+record-partner_types = Тип организации
+record-partner_rating_history = Изменение рейтинга
+record-products = Продукт
+record-product_types = Тип продукта
+record-product_materials = Состав продукта
+record-materials = Материал
+record-suppliers = Поставщик
+record-sales = Продажа
+record-documents = Документ
+
+field-id = Идентификатор
+field-partner_name = Название организации
+field-supplier_name = Название организации
+field-partner_type = Тип организации
+field-supplier_type = Тип организации
+field-product_name = Название
+field-material_name = Название
+field-director = Директор
+field-email = E-Mail
+field-phone = Телефон
+field-legal_address = Юридический адрес
+field-inn = ИНН
+field-rating = Рейтинг
+field-old_rating = Рейтинг
+field-new_rating = Рейтинг
+field-comment = Причина изменения
+field-postal_index = Индекс
+field-region = Регион
+field-city = Город
+field-street = Улица
+field-building = Дом
+field-product_type = Тип продукта
+field-coefficient = Коэффициент
+field-article_number = Артикул
+field-minimum_cost = Минимальная цена
+field-unit = Единица измерения
+field-price = Цена за единицу
+field-defect_percentage = Процент брака
+field-quantity = Количество
+field-sale_date = Дата продажи
+field-document_type = Тип документа
+field-number = Номер
+field-issue_date = Дата выдачи
+field-product_id = Продукт
+field-partner_id = Партнер
+field-material_id = Материал
+field-supplier_id = Поставщик
+field-sale_id = Продажа
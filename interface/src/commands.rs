@@ -0,0 +1,175 @@
+//! Keyboard shortcuts and the command palette: everything the header and the
+//! side panels offer, reachable without the mouse.
+
+use eframe::egui::{self, Align2, Key, KeyboardShortcut, Modifiers, RichText};
+
+use crate::i18n::tr;
+use crate::views::Views;
+
+pub const BACK: KeyboardShortcut = KeyboardShortcut::new(Modifiers::ALT, Key::ArrowLeft);
+pub const FORWARD: KeyboardShortcut = KeyboardShortcut::new(Modifiers::ALT, Key::ArrowRight);
+pub const NEW_RECORD: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::N);
+pub const SEARCH: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::F);
+pub const PALETTE: KeyboardShortcut = KeyboardShortcut::new(Modifiers::COMMAND, Key::K);
+
+/// Entries of the palette shown at once; the rest are reached by typing more.
+const MAX_ENTRIES: usize = 12;
+
+/// Something the user asked for by a shortcut or in the palette.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+    Back,
+    Forward,
+    Open(Views),
+    /// Opens the form adding a record to the current view.
+    NewRecord,
+    /// Opens the view and scrolls to the record with the id.
+    Reveal(Views, String),
+}
+
+/// A line of the palette.
+pub struct Entry {
+    pub title: String,
+    /// What the entry is, e.g. "Partner", shown next to the title.
+    pub kind: String,
+    pub shortcut: Option<KeyboardShortcut>,
+    pub command: Command,
+}
+
+impl Entry {
+    pub fn action(title: String, shortcut: Option<KeyboardShortcut>, command: Command) -> Self {
+        Entry { title, kind: tr!("action"), shortcut, command }
+    }
+
+    pub fn record(title: String, kind: String, command: Command) -> Self {
+        Entry { title, kind, shortcut: None, command }
+    }
+}
+
+/// The command palette opened by [`PALETTE`]: a search field over actions
+/// and records, chosen with the arrows and Enter.
+#[derive(Default)]
+pub struct Palette {
+    open: bool,
+    query: String,
+    selected: usize,
+    /// Loaded once when the palette opens, rather than queried every frame.
+    entries: Vec<Entry>,
+}
+
+impl Palette {
+    pub fn open(&mut self, entries: Vec<Entry>) {
+        self.open = true;
+        self.query.clear();
+        self.selected = 0;
+        self.entries = entries;
+    }
+
+    pub fn close(&mut self) {
+        self.open = false;
+        self.entries.clear();
+    }
+
+    /// Shows the palette over the view. Returns the command of the chosen entry.
+    pub fn show(&mut self, ctx: &egui::Context) -> Option<Command> {
+        if !self.open {
+            return None;
+        }
+
+        let mut matches: Vec<(i32, &Entry)> = self
+            .entries
+            .iter()
+            .filter_map(|entry| fuzzy_score(&self.query, &entry.title).map(|score| (score, entry)))
+            .collect();
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+        matches.truncate(MAX_ENTRIES);
+
+        let (up, down, enter, escape) = ctx.input_mut(|i| {
+            (
+                i.consume_key(Modifiers::NONE, Key::ArrowUp),
+                i.consume_key(Modifiers::NONE, Key::ArrowDown),
+                i.consume_key(Modifiers::NONE, Key::Enter),
+                i.consume_key(Modifiers::NONE, Key::Escape),
+            )
+        });
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        if down {
+            self.selected += 1;
+        }
+        self.selected = self.selected.min(matches.len().saturating_sub(1));
+
+        let mut chosen = enter.then_some(self.selected);
+        egui::Window::new(tr!("command-palette"))
+            .title_bar(false)
+            .collapsible(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_TOP, [0.0, 60.0])
+            .min_width(400.0)
+            .show(ctx, |ui| {
+                let query = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text(tr!("palette-hint"))
+                        .desired_width(f32::INFINITY),
+                );
+                query.request_focus();
+                if query.changed() {
+                    self.selected = 0;
+                }
+
+                ui.separator();
+                if matches.is_empty() {
+                    ui.label(tr!("nothing-found"));
+                }
+                matches.iter().enumerate().for_each(|(index, (_, entry))| {
+                    ui.horizontal(|ui| {
+                        if ui.selectable_label(index == self.selected, &entry.title).clicked() {
+                            chosen = Some(index);
+                        }
+                        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                            if let Some(shortcut) = &entry.shortcut {
+                                ui.label(RichText::new(ctx.format_shortcut(shortcut)).weak());
+                            }
+                            ui.label(RichText::new(&entry.kind).weak());
+                        });
+                    });
+                });
+            });
+
+        let command = chosen.and_then(|index| matches.get(index)).map(|(_, entry)| entry.command.clone());
+        if escape || command.is_some() {
+            self.close();
+        }
+        command
+    }
+}
+
+/// How well `query` matches `text`: all characters of the query must appear
+/// in the text in order, ignoring case. Runs of characters and matches at
+/// the start of words score higher, gaps lower. An empty query matches
+/// everything equally.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<i32> {
+    let text: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars().flat_map(char::to_lowercase).filter(|c| !c.is_whitespace()) {
+        let found = position + text[position..].iter().position(|c| *c == wanted)?;
+
+        score += 1;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !text[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - position) as i32;
+
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(score)
+}
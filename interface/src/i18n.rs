@@ -0,0 +1,244 @@
+//! Translations of the interface. Messages live in Fluent files under
+//! `locales/`, one per [`Language`], and are looked up with [`tr!`]. Dates and
+//! numbers shown to the user go through [`format_date`] and [`format_number`].
+
+use std::cell::RefCell;
+
+use chrono::Datelike;
+use demo_core::models::documents::DocumentType;
+use demo_core::models::duplicates::{DuplicateReason, PartnerField};
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use serde::{Deserialize, Serialize};
+use unic_langid::{langid, LanguageIdentifier};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    Russian,
+    English,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::Russian, Language::English];
+
+    /// Name of the language in itself, for the switcher.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::Russian => "Русский",
+            Language::English => "English",
+        }
+    }
+
+    fn id(&self) -> LanguageIdentifier {
+        match self {
+            Language::Russian => langid!("ru"),
+            Language::English => langid!("en"),
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            Language::Russian => include_str!("../locales/ru.ftl"),
+            Language::English => include_str!("../locales/en.ftl"),
+        }
+    }
+}
+
+struct Localizer {
+    language: Language,
+    bundle: FluentBundle<FluentResource>,
+}
+
+impl Localizer {
+    fn new(language: Language) -> Self {
+        let resource = FluentResource::try_new(language.source().to_string())
+            .unwrap_or_else(|(resource, errors)| {
+                tracing::error!("Ошибки в переводе {:?}: {:?}", language, errors);
+                resource
+            });
+
+        let mut bundle = FluentBundle::new(vec![language.id()]);
+        // egui draws the Unicode isolation marks around arguments as boxes
+        bundle.set_use_isolating(false);
+        bundle
+            .add_resource(resource)
+            .unwrap_or_else(|errors| tracing::error!("Повторяющиеся сообщения {:?}: {:?}", language, errors));
+
+        Localizer { language, bundle }
+    }
+}
+
+thread_local! {
+    static LOCALIZER: RefCell<Localizer> = RefCell::new(Localizer::new(Language::default()));
+}
+
+pub fn language() -> Language {
+    LOCALIZER.with(|localizer| localizer.borrow().language)
+}
+
+pub fn set_language(language: Language) {
+    LOCALIZER.with(|localizer| {
+        if localizer.borrow().language != language {
+            *localizer.borrow_mut() = Localizer::new(language);
+        }
+    });
+}
+
+/// The message `id` of the current language, or `id` itself if there is none.
+pub fn translate(id: &str, args: Option<&FluentArgs>) -> String {
+    LOCALIZER.with(|localizer| {
+        let localizer = localizer.borrow();
+        let Some(pattern) = localizer.bundle.get_message(id).and_then(|message| message.value()) else {
+            return id.to_string();
+        };
+
+        let mut errors = vec![];
+        localizer.bundle.format_pattern(pattern, args, &mut errors).into_owned()
+    })
+}
+
+/// The translated message, with Fluent arguments given as `name = value`:
+/// `tr!("addresses-unparsed", count = unparsed.len())`.
+macro_rules! tr {
+    ($id:expr) => {
+        $crate::i18n::translate($id, None)
+    };
+    ($id:expr, $($name:ident = $value:expr),+ $(,)?) => {{
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)+
+        $crate::i18n::translate($id, Some(&args))
+    }};
+}
+pub(crate) use tr;
+
+/// A date the way the current language writes it.
+pub fn format_date(date: chrono::NaiveDate) -> String {
+    let (year, month, day) = (date.year(), date.month(), date.day());
+    match language() {
+        Language::Russian => format!("{:02}.{:02}.{}", day, month, year),
+        Language::English => format!("{:02}/{:02}/{}", month, day, year),
+    }
+}
+
+/// `value` with `decimals` digits after the point, grouped by thousands the
+/// way the current language does it.
+pub fn format_number(value: f64, decimals: usize) -> String {
+    let (thousands, point) = match language() {
+        Language::Russian => ('\u{a0}', ','),
+        Language::English => (',', '.'),
+    };
+
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = formatted.split_once('.').unwrap_or((&formatted, ""));
+
+    let mut result = String::new();
+    if value < 0.0 && formatted.chars().any(|c| c.is_ascii_digit() && c != '0') {
+        result.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            result.push(thousands);
+        }
+        result.push(digit);
+    }
+    if !fraction.is_empty() {
+        result.push(point);
+        result.push_str(fraction);
+    }
+    result
+}
+
+/// Text of a rule of the shared models in the current language.
+pub fn rule_message(rule: &demo_core::Rule) -> String {
+    use demo_core::Rule;
+
+    match rule {
+        Rule::Required => tr!("rule-required"),
+        Rule::AddressParts { found } => tr!("rule-address-parts", found = *found),
+        Rule::PostalIndex { index } => tr!("rule-postal-index", index = index.as_str()),
+        Rule::DistinctMerge => tr!("rule-distinct-merge"),
+        Rule::Positive => tr!("rule-positive"),
+    }
+}
+
+/// What two partners have in common, in the current language.
+pub fn duplicate_reasons(reasons: &[DuplicateReason]) -> String {
+    reasons
+        .iter()
+        .map(|reason| match reason {
+            DuplicateReason::SameInn => tr!("reason-same-inn"),
+            DuplicateReason::SimilarName => tr!("reason-similar-name"),
+            DuplicateReason::SimilarEmail => tr!("reason-similar-email"),
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Label of a partner field in the merge tool, as the partner form calls it.
+pub fn partner_field(field: PartnerField) -> String {
+    match field {
+        PartnerField::PartnerType => tr!("organization-type"),
+        PartnerField::PartnerName => tr!("organization-name"),
+        PartnerField::Director => tr!("organization-director"),
+        PartnerField::Email => tr!("email"),
+        PartnerField::Phone => tr!("contact-phone"),
+        PartnerField::Address => tr!("address"),
+        PartnerField::Inn => tr!("inn"),
+        PartnerField::Rating => tr!("rating"),
+    }
+}
+
+/// Title of a printed document in the current language.
+pub fn document_title(document_type: DocumentType) -> String {
+    match document_type {
+        DocumentType::Invoice => tr!("document-invoice"),
+        DocumentType::DeliveryNote => tr!("document-delivery-note"),
+    }
+}
+
+/// Text of an error of the shared models in the current language. Plain
+/// messages of the models are shown as they are.
+pub fn error_message(error: &demo_core::Error) -> String {
+    use demo_core::Error;
+
+    // Tables and columns without a message are shown by their names
+    let label = |prefix: &str, name: &str| {
+        let id = [prefix, name].concat();
+        let text = translate(&id, None);
+        if text == id { name.to_string() } else { text }
+    };
+    let record = |table: &str| label("record-", table);
+    let field = |column: &str| label("field-", column);
+
+    match error {
+        Error::UniqueViolation { table, columns, .. } => tr!(
+            "error-unique",
+            record = record(table),
+            fields = columns.iter().map(|column| ["«", &field(column), "»"].concat()).collect::<Vec<_>>().join(", "),
+            count = columns.len(),
+        ),
+        Error::ForeignKeyViolation { table: Some(table), column: Some(column), in_use: true, .. } => {
+            tr!("error-foreign-key-in-use", record = record(table), field = field(column))
+        }
+        Error::ForeignKeyViolation { table: Some(table), column: Some(column), .. } => {
+            tr!("error-foreign-key-field", record = record(table), field = field(column))
+        }
+        Error::ForeignKeyViolation { table: Some(table), column: None, .. } => {
+            tr!("error-foreign-key-record", record = record(table))
+        }
+        Error::ForeignKeyViolation { table: None, .. } => tr!("error-foreign-key"),
+        Error::NotFound { table: Some(table), key: Some(key) } => {
+            tr!("error-not-found-key", record = record(table), key = key.as_str())
+        }
+        Error::NotFound { table: Some(table), key: None } => tr!("error-not-found-record", record = record(table)),
+        Error::NotFound { table: None, .. } => tr!("error-not-found"),
+        Error::Validation { table, column, rule, .. } => tr!(
+            "error-validation",
+            record = record(table),
+            field = field(column),
+            message = rule_message(rule),
+        ),
+        Error::DatabaseError(e) => tr!("error-database", error = e.to_string()),
+        Error::Any(message) => message.clone(),
+    }
+}
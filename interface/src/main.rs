@@ -1,28 +1,99 @@
 #![windows_subsystem = "windows"]
-mod models;
+mod commands;
+mod i18n;
+mod notifications;
+mod paging;
+mod settings;
+#[cfg(test)]
+mod ui_tests;
 mod views;
-
-use eframe::egui::{self, Color32, Context, Rounding, Stroke};
+mod widgets;
+
+use commands::Palette;
+use eframe::egui;
+use futures::executor::block_on;
+use notifications::Notifications;
+use serde::{Deserialize, Serialize};
+use settings::Settings;
 use sqlx::{sqlite::SqliteConnectOptions, SqlitePool};
-use views::{Views, ViewsData};
-
 use std::str::FromStr;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use views::{SalesPreset, Views, ViewsData};
+
+/// Directory of the log files to send to support.
+const LOG_DIR: &str = "logs";
+/// Logs rotate daily; older files than this many days are removed.
+const LOG_FILES: usize = 14;
+/// Key of the chosen [`i18n::Language`] in the eframe storage.
+const LANGUAGE_KEY: &str = "language";
+/// Key of the [`Settings`] in the eframe storage.
+const SETTINGS_KEY: &str = "settings";
+/// Keys of the navigation state in the eframe storage, restored on the next start.
+const HISTORY_KEY: &str = "history";
+const CURRENT_VIEW_KEY: &str = "current_view";
+const VIEWS_DATA_KEY: &str = "views_data";
+/// Key of the saved [`SalesPreset`]s in the eframe storage.
+const SALES_PRESETS_KEY: &str = "sales_presets";
 
 fn main() {
+    let _log_guard = init_logging();
+
     let native_options = eframe::NativeOptions::default();
 
-    let db = sqlx::SqlitePool::connect_lazy_with(
-        SqliteConnectOptions::from_str("sqlite://data.sqlite").unwrap(),
-    );
+    let database_url =
+        std::env::var("DATABASE_URL").unwrap_or(demo_core::DEFAULT_DATABASE_URL.to_string());
+    tracing::info!("Подключение к базе данных {}", database_url);
+    let connected = block_on(demo_core::connect(&database_url)).inspect_err(|e| tracing::error!("{}", e));
 
     eframe::run_native(
         "My egui App",
         native_options,
-        Box::new(|cc| Ok(Box::new(MyEguiApp::new(cc, db)))),
+        Box::new(move |cc| {
+            Ok(Box::new(match connected {
+                Ok(db) => MyEguiApp::new(cc, db),
+                // The window opens anyway to tell why nothing loads; the views
+                // report the failure again on every query of the lazy pool.
+                Err(e) => {
+                    let options = SqliteConnectOptions::from_str(&database_url).unwrap_or_default();
+                    let mut app = MyEguiApp::new(cc, SqlitePool::connect_lazy_with(options));
+                    app.notifications.error(&e);
+                    app
+                }
+            }))
+        }),
     )
     .unwrap();
 }
 
+/// Writes the log to a daily rotated file in [`LOG_DIR`]. The guard flushes
+/// the log when dropped at the end of `main`.
+fn init_logging() -> Option<WorkerGuard> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("interface")
+        .filename_suffix("log")
+        .max_log_files(LOG_FILES)
+        .build(LOG_DIR);
+
+    match appender {
+        Ok(appender) => {
+            let (writer, guard) = tracing_appender::non_blocking(appender);
+            tracing_subscriber::fmt()
+                .with_writer(writer)
+                .with_ansi(false)
+                .with_max_level(tracing::Level::INFO)
+                .init();
+            Some(guard)
+        }
+        Err(e) => {
+            eprintln!("Не удалось открыть журнал в {}: {}", LOG_DIR, e);
+            None
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
 struct History {
     previous: Vec<Views>,
     next: Vec<Views>,
@@ -33,7 +104,11 @@ struct MyEguiApp {
     db: SqlitePool,
     history: History,
     current_view: Views,
-    view_data: ViewsData
+    view_data: ViewsData,
+    notifications: Notifications,
+    palette: Palette,
+    settings: Settings,
+    sales_presets: Vec<SalesPreset>,
 }
 
 impl MyEguiApp {
@@ -45,82 +120,65 @@ impl MyEguiApp {
     }
 
     fn go_back(&mut self) {
-        match self.history.previous.last() {
-            Some(_) => {
-                let current_view = self.current_view;
-
-                self.history.next.push(current_view.clone());
-
-
-                self.current_view = if let Some(previous) = self.history.previous.pop() {
-                    previous
-                } else {
-                    Views::MainView
-                };
-            }
-            _ => (),
+        if let Some(previous) = self.history.previous.pop() {
+            self.history.next.push(self.current_view);
+            self.current_view = previous;
         }
     }
 
     fn go_forward(&mut self) {
-        match self.history.next.last() {
-            Some(_) => {
-                let current_view = self.current_view;
-
-                self.history.previous.push(current_view.clone());
-
-                self.current_view = if let Some(next) = self.history.next.pop() {
-                    next
-                } else {
-                    current_view.clone()
-                };
-            }
-            _ => (),
+        if let Some(next) = self.history.next.pop() {
+            self.history.previous.push(self.current_view);
+            self.current_view = next;
         }
     }
+
+    /// Forgets the history and the drafts, as if the application was started
+    /// for the first time. The current view stays open.
+    fn discard_session(&mut self) {
+        self.history = History::default();
+        self.view_data = ViewsData::default();
+    }
 }
 
 impl MyEguiApp {
     fn new(cc: &eframe::CreationContext<'_>, db: sqlx::SqlitePool) -> Self {
         // Customize egui here with cc.egui_ctx.set_fonts and cc.egui_ctx.set_visuals.
-        // Restore app state using cc.storage (requires the "persistence" feature).
         // Use the cc.gl (a glow::Context) to create graphics shaders and buffers that you can use
         // for e.g. egui::PaintCallback.
 
-        cc.egui_ctx.set_visuals_of(egui::Theme::Light,egui::Visuals {
-            dark_mode: false,
-            widgets: egui::style::Widgets {
-                inactive: egui::style::WidgetVisuals {
-                    weak_bg_fill: Color32::from_hex("#67ba80").unwrap(),
-                    bg_fill: Color32::from_gray(230),
-                    bg_stroke: Default::default(), // separators, indentation lines
-                    fg_stroke: Stroke::new(1.0, Color32::from_gray(60)), // normal text color
-                    rounding: Rounding::same(2.0),
-                    expansion: 0.0,
-                },
-                hovered: egui::style::WidgetVisuals {
-                    weak_bg_fill: Color32::DARK_GREEN,
-                    bg_fill: Color32::DARK_GREEN,
-                    bg_stroke: Stroke::new(1.0, Color32::from_gray(105)), // e.g. hover over window edge or button
-                    fg_stroke: Stroke::new(1.5, Color32::from_gray(240)),
-                    rounding: Rounding::same(3.0),
-                    expansion: 1.0,
-                },
-                ..egui::style::Widgets::light()
-            },
-            ..egui::Visuals::light()
-        });
-
-        cc.egui_ctx.set_theme(egui::Theme::Light);
+        if let Some(language) = cc.storage.and_then(|storage| eframe::get_value(storage, LANGUAGE_KEY)) {
+            i18n::set_language(language);
+        }
+
+        settings::install_visuals(&cc.egui_ctx);
+        let settings: Settings = cc
+            .storage
+            .and_then(|storage| eframe::get_value(storage, SETTINGS_KEY))
+            .unwrap_or_default();
+        settings.apply(&cc.egui_ctx);
 
         Self {
             db,
-            history: History {
-                previous: vec![],
-                next: vec![],
-            },
-            current_view: Views::MainView,
-            view_data: ViewsData::default()
+            history: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, HISTORY_KEY))
+                .unwrap_or_default(),
+            current_view: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, CURRENT_VIEW_KEY))
+                .unwrap_or(Views::MainView),
+            view_data: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, VIEWS_DATA_KEY))
+                .unwrap_or_default(),
+            notifications: Notifications::default(),
+            palette: Palette::default(),
+            settings,
+            sales_presets: cc
+                .storage
+                .and_then(|storage| eframe::get_value(storage, SALES_PRESETS_KEY))
+                .unwrap_or_default(),
         }
     }
 }
@@ -129,40 +187,13 @@ impl eframe::App for MyEguiApp {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         views::show(self, ctx);
     }
-}
 
-pub(crate) type Result<T> = std::result::Result<T, Error>;
-
-#[derive(Debug)]
-enum Error {
-    DatabaseError(sqlx::Error),
-    Any(String)
-}
-
-impl Error {
-    pub fn show(&self, ctx: &Context) {
-        let mut open = true;
-        egui::Window::new("Ошибка").open(&mut open).show(ctx, |ui| {
-            ui.vertical(|ui| {
-                ui.label(self.to_string());
-            });
-        });
+    fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        eframe::set_value(storage, LANGUAGE_KEY, &i18n::language());
+        eframe::set_value(storage, SETTINGS_KEY, &self.settings);
+        eframe::set_value(storage, HISTORY_KEY, &self.history);
+        eframe::set_value(storage, CURRENT_VIEW_KEY, &self.current_view);
+        eframe::set_value(storage, VIEWS_DATA_KEY, &self.view_data);
+        eframe::set_value(storage, SALES_PRESETS_KEY, &self.sales_presets);
     }
 }
-
-impl From<sqlx::Error> for Error {
-    fn from(value: sqlx::Error) -> Self {
-        Self::DatabaseError(value)
-    }
-}
-
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Error::DatabaseError(err) => write!(f, "Ошибка при выполнении запроса:\n{}", err),
-            Error::Any(msg) => write!(f, "{}",msg),
-        }
-    }
-}
-
-impl std::error::Error for Error {}
@@ -1,4 +0,0 @@
-pub mod partners;
-pub mod sales;
-pub mod products;
-pub mod product_types;
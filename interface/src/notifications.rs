@@ -0,0 +1,204 @@
+//! Messages for the user from every view, shown as toasts stacked in the
+//! corner of the window. Each message is also written to the log for support.
+
+use std::time::{Duration, Instant};
+
+use eframe::egui::{self, Align2, Color32, Label, RichText, Stroke};
+
+use crate::i18n::{self, tr};
+use crate::settings::BRAND_GREEN;
+
+/// How long a toast that needs no reaction stays on screen.
+const TIMEOUT: Duration = Duration::from_secs(5);
+/// Older toasts are dropped beyond this many.
+const MAX_TOASTS: usize = 5;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Severity {
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn icon(&self) -> &'static str {
+        match self {
+            Severity::Info => "ℹ",
+            Severity::Success => "✔",
+            Severity::Warning => "⚠",
+            Severity::Error => "❌",
+        }
+    }
+
+    fn color(&self) -> Color32 {
+        match self {
+            Severity::Info => Color32::from_rgb(70, 130, 180),
+            Severity::Success => Color32::from_hex(BRAND_GREEN).unwrap_or(Color32::DARK_GREEN),
+            Severity::Warning => Color32::from_rgb(230, 150, 0),
+            Severity::Error => Color32::RED,
+        }
+    }
+
+    /// Warnings and errors stay until the user dismisses them.
+    fn expires(&self) -> bool {
+        matches!(self, Severity::Info | Severity::Success)
+    }
+}
+
+struct Toast {
+    id: u64,
+    severity: Severity,
+    message: String,
+    details: Option<String>,
+    created: Instant,
+}
+
+/// The toasts of the application, see [`show`](Notifications::show).
+#[derive(Default)]
+pub struct Notifications {
+    toasts: Vec<Toast>,
+    next_id: u64,
+    /// Message and details of the error opened for details.
+    opened: Option<(String, String)>,
+}
+
+impl Notifications {
+    /// Adds a toast. The same message as one already shown only restarts its
+    /// timer, so an error repeated every frame neither floods the screen nor the log.
+    pub fn push(&mut self, severity: Severity, message: impl Into<String>, details: Option<String>) {
+        let message = message.into();
+
+        if let Some(toast) = self
+            .toasts
+            .iter_mut()
+            .find(|toast| toast.severity == severity && toast.message == message)
+        {
+            toast.created = Instant::now();
+            return;
+        }
+
+        match severity {
+            Severity::Info | Severity::Success => tracing::info!(details = details.as_deref(), "{}", message),
+            Severity::Warning => tracing::warn!(details = details.as_deref(), "{}", message),
+            Severity::Error => tracing::error!(details = details.as_deref(), "{}", message),
+        }
+
+        self.toasts.push(Toast {
+            id: self.next_id,
+            severity,
+            message,
+            details,
+            created: Instant::now(),
+        });
+        self.next_id += 1;
+
+        if self.toasts.len() > MAX_TOASTS {
+            self.toasts.remove(0);
+        }
+    }
+
+    pub fn info(&mut self, message: impl Into<String>) {
+        self.push(Severity::Info, message, None);
+    }
+
+    pub fn success(&mut self, message: impl Into<String>) {
+        self.push(Severity::Success, message, None);
+    }
+
+    pub fn warning(&mut self, message: impl Into<String>) {
+        self.push(Severity::Warning, message, None);
+    }
+
+    /// An error of the shared models. All but plain messages get details for
+    /// support: the structured error, or the SQLite error code of the rest.
+    pub fn error(&mut self, error: &demo_core::Error) {
+        self.push(Severity::Error, i18n::error_message(error), details(error));
+    }
+
+    /// Draws the toasts in the bottom right corner and the details window.
+    pub fn show(&mut self, ctx: &egui::Context) {
+        let now = Instant::now();
+        self.toasts
+            .retain(|toast| !toast.severity.expires() || now.duration_since(toast.created) < TIMEOUT);
+        if self.toasts.iter().any(|toast| toast.severity.expires()) {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+
+        let mut dismissed = None;
+        let mut opened = None;
+        egui::Area::new(egui::Id::new("notifications"))
+            .anchor(Align2::RIGHT_BOTTOM, [-8.0, -8.0])
+            .order(egui::Order::Foreground)
+            .show(ctx, |ui| {
+                ui.set_max_width(360.0);
+                self.toasts.iter().for_each(|toast| {
+                    let color = toast.severity.color();
+                    egui::Frame::popup(ui.style())
+                        .stroke(Stroke::new(1.0, color))
+                        .show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new(toast.severity.icon()).color(color).size(18.0));
+                                ui.add(Label::new(&toast.message).wrap());
+                                if ui.small_button("✖").clicked() {
+                                    dismissed = Some(toast.id);
+                                }
+                            });
+                            if let Some(details) = &toast.details {
+                                if ui.small_button(tr!("details")).clicked() {
+                                    opened = Some((toast.message.clone(), details.clone()));
+                                }
+                            }
+                        });
+                });
+            });
+
+        if let Some(id) = dismissed {
+            self.toasts.retain(|toast| toast.id != id);
+        }
+        if opened.is_some() {
+            self.opened = opened;
+        }
+
+        let mut open = self.opened.is_some();
+        if let Some((message, details)) = &self.opened {
+            egui::Window::new(tr!("error-details")).open(&mut open).show(ctx, |ui| {
+                ui.label(message);
+                ui.separator();
+                ui.label(RichText::new(details).monospace());
+                if ui.button(tr!("copy")).clicked() {
+                    ui.ctx().copy_text([message.as_str(), "\n", details].concat());
+                }
+            });
+        }
+        if !open {
+            self.opened = None;
+        }
+    }
+}
+
+/// What support needs to know about a database error beyond its message:
+/// the code and message of the SQLite error it comes from, if any, followed
+/// by the structured error.
+fn details(error: &demo_core::Error) -> Option<String> {
+    let sqlite = std::error::Error::source(error)
+        .and_then(|source| source.downcast_ref::<sqlx::Error>())
+        .and_then(|source| match source {
+            sqlx::Error::Database(e) => Some(format!(
+                "{}\n{}",
+                tr!("sqlite-code", code = e.code().map(String::from).unwrap_or_else(|| tr!("no-code"))),
+                e.message()
+            )),
+            _ => None,
+        });
+
+    match error {
+        demo_core::Error::DatabaseError(sqlx::Error::Database(_)) => sqlite,
+        demo_core::Error::DatabaseError(e) => Some(format!("{:?}", e)),
+        demo_core::Error::Any(_) => None,
+        error => Some(match sqlite {
+            Some(sqlite) => format!("{}\n{:?}", sqlite, error),
+            None => format!("{:?}", error),
+        }),
+    }
+}
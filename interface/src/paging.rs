@@ -0,0 +1,246 @@
+//! Lists loaded page by page as the user scrolls, so a view opens as fast on
+//! years of sales as on an empty database.
+
+use std::future::Future;
+
+use demo_core::models::partners::Partner;
+use demo_core::models::products::Product;
+use demo_core::models::repository::{Cursor, Entity, Filter, Repository};
+use demo_core::models::sales::{self, Sale, SaleDetails};
+use sqlx::SqlitePool;
+
+/// Records loaded by one query.
+pub const PAGE_SIZE: i64 = 50;
+
+/// Records a [`Pager`] loads: the rows of the table `Table`, possibly with
+/// the names of the records they refer to.
+pub trait Paged: Clone {
+    type Table: Repository;
+
+    fn cursor(&self) -> Cursor;
+
+    fn page(
+        db: &SqlitePool,
+        filters: &[Filter],
+        after: Option<&Cursor>,
+        limit: i64,
+    ) -> impl Future<Output = demo_core::Result<Vec<Self>>>;
+
+    /// [`Repository::version`] of every table the records are read from; the
+    /// loaded pages are stale once any of them changes.
+    fn versions(db: &SqlitePool) -> impl Future<Output = demo_core::Result<Vec<i64>>> {
+        async move { Ok(vec![Self::Table::version(db).await?]) }
+    }
+}
+
+impl Paged for Partner {
+    type Table = Partner;
+
+    fn cursor(&self) -> Cursor {
+        Entity::cursor(self)
+    }
+
+    async fn page(db: &SqlitePool, filters: &[Filter], after: Option<&Cursor>, limit: i64) -> demo_core::Result<Vec<Self>> {
+        <Partner as Repository>::page(db, filters, after, limit).await
+    }
+}
+
+impl Paged for Product {
+    type Table = Product;
+
+    fn cursor(&self) -> Cursor {
+        Entity::cursor(self)
+    }
+
+    async fn page(db: &SqlitePool, filters: &[Filter], after: Option<&Cursor>, limit: i64) -> demo_core::Result<Vec<Self>> {
+        <Product as Repository>::page(db, filters, after, limit).await
+    }
+}
+
+impl Paged for SaleDetails {
+    type Table = Sale;
+
+    fn cursor(&self) -> Cursor {
+        self.sale.cursor()
+    }
+
+    async fn page(db: &SqlitePool, filters: &[Filter], after: Option<&Cursor>, limit: i64) -> demo_core::Result<Vec<Self>> {
+        sales::get_all_detailed(db, filters, after, limit).await
+    }
+
+    async fn versions(db: &SqlitePool) -> demo_core::Result<Vec<i64>> {
+        Ok(vec![Sale::version(db).await?, Partner::version(db).await?, Product::version(db).await?])
+    }
+}
+
+/// The loaded part of a list. [`refresh`](Pager::refresh) keeps it up to date
+/// with the table, [`load_more`](Pager::load_more) extends it by a page.
+pub struct Pager<T> {
+    items: Vec<T>,
+    total: i64,
+    filters: Vec<Filter>,
+    /// [`Paged::versions`] when the items were loaded.
+    versions: Option<Vec<i64>>,
+    done: bool,
+}
+
+impl<T> Default for Pager<T> {
+    fn default() -> Self {
+        Pager {
+            items: vec![],
+            total: 0,
+            filters: vec![],
+            versions: None,
+            done: false,
+        }
+    }
+}
+
+impl<T: Paged> Pager<T> {
+    /// Starts over from the first page when the filters changed. When only
+    /// the table did, loads as many records as were shown again.
+    pub async fn refresh(&mut self, db: &SqlitePool, filters: Vec<Filter>) -> demo_core::Result<()> {
+        let versions = T::versions(db).await?;
+        if filters != self.filters || self.versions.is_none() {
+            *self = Pager { filters, ..Default::default() };
+            self.reload(db, PAGE_SIZE, versions).await
+        } else if self.versions.as_ref() != Some(&versions) {
+            let loaded = (self.items.len() as i64).max(PAGE_SIZE);
+            self.reload(db, loaded, versions).await
+        } else {
+            Ok(())
+        }
+    }
+
+    async fn reload(&mut self, db: &SqlitePool, limit: i64, versions: Vec<i64>) -> demo_core::Result<()> {
+        self.items = T::page(db, &self.filters, None, limit).await?;
+        self.done = (self.items.len() as i64) < limit;
+        self.total = T::Table::count(db, &self.filters).await?;
+        self.versions = Some(versions);
+        Ok(())
+    }
+
+    /// Loads the page after the last loaded record.
+    pub async fn load_more(&mut self, db: &SqlitePool) -> demo_core::Result<()> {
+        if self.done {
+            return Ok(());
+        }
+
+        let after = self.items.last().map(|item| item.cursor());
+        let page = T::page(db, &self.filters, after.as_ref(), PAGE_SIZE).await?;
+        self.done = (page.len() as i64) < PAGE_SIZE;
+        self.items.extend(page);
+        Ok(())
+    }
+
+    /// Loads pages until one of the loaded records is `wanted`, or the list ends.
+    pub async fn load_until(&mut self, db: &SqlitePool, wanted: impl Fn(&T) -> bool) -> demo_core::Result<()> {
+        while !self.items.iter().any(&wanted) && !self.done {
+            self.load_more(db).await?;
+        }
+        Ok(())
+    }
+
+    pub fn items(&self) -> &[T] {
+        &self.items
+    }
+
+    /// Records matching the filters, loaded or not.
+    pub fn total(&self) -> i64 {
+        self.total
+    }
+
+    pub fn has_more(&self) -> bool {
+        !self.done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use demo_core::models::product_types::ProductType;
+    use demo_core::models::repository::Filter;
+    use demo_core::test_util::connect_in_memory;
+    use futures::executor::block_on;
+
+    use super::*;
+
+    /// A partner and a product with `count` sales of one day between them.
+    fn sales(count: usize) -> (SqlitePool, Partner) {
+        let db = block_on(connect_in_memory()).unwrap();
+        let partner = Partner { partner_type: "ООО".into(), partner_name: "Паркет 29".into(), ..Partner::default() };
+        block_on(partner.insert(&db)).unwrap();
+        block_on(ProductType::new("Ламинат".into(), 2.35).insert(&db)).unwrap();
+        let product = Product::new("Ламинат".into(), "Ламинат Дуб 12 мм".into(), "7750282".into(), 1799);
+        block_on(product.insert(&db)).unwrap();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 4, 1).unwrap();
+        for quantity in 1..=count as i64 {
+            block_on(Sale::new(product.id.clone(), quantity, day, partner.id.clone()).insert(&db)).unwrap();
+        }
+        (db, partner)
+    }
+
+    fn ids(pager: &Pager<SaleDetails>) -> Vec<String> {
+        pager.items().iter().map(|details| details.sale.id.clone()).collect()
+    }
+
+    #[test]
+    fn pages_load_one_after_another_to_the_end() {
+        let (db, _) = sales(120);
+        let mut pager = Pager::<SaleDetails>::default();
+
+        block_on(pager.refresh(&db, vec![])).unwrap();
+        assert_eq!((pager.items().len(), pager.total(), pager.has_more()), (50, 120, true));
+        block_on(pager.load_more(&db)).unwrap();
+        assert_eq!(pager.items().len(), 100);
+        block_on(pager.load_more(&db)).unwrap();
+        assert_eq!((pager.items().len(), pager.has_more()), (120, false));
+        block_on(pager.load_more(&db)).unwrap();
+        assert_eq!(pager.items().len(), 120);
+
+        let mut loaded = ids(&pager);
+        let listed: Vec<String> = block_on(Sale::list(&db, &[], None)).unwrap().into_iter().map(|sale| sale.id).collect();
+        assert_eq!(loaded, listed);
+        loaded.sort();
+        loaded.dedup();
+        assert_eq!(loaded.len(), 120);
+    }
+
+    #[test]
+    fn refresh_keeps_what_was_shown_until_the_filters_change() {
+        let (db, partner) = sales(120);
+        let mut pager = Pager::<SaleDetails>::default();
+        block_on(pager.refresh(&db, vec![])).unwrap();
+        block_on(pager.load_more(&db)).unwrap();
+
+        // Nothing changed: the loaded pages stay as they are
+        block_on(pager.refresh(&db, vec![])).unwrap();
+        assert_eq!(pager.items().len(), 100);
+
+        // The table changed: as many records as were shown are loaded again
+        let product_id = pager.items()[0].sale.product_id.clone();
+        let day = chrono::NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        let sale = Sale::new(product_id, 500, day, partner.id.clone());
+        block_on(sale.insert(&db)).unwrap();
+        block_on(pager.refresh(&db, vec![])).unwrap();
+        assert_eq!((pager.items().len(), pager.total()), (100, 121));
+        assert_eq!(pager.items()[0].sale.id, sale.id);
+
+        // Other filters: from the first page
+        block_on(pager.refresh(&db, vec![Filter::eq("quantity", 500)])).unwrap();
+        assert_eq!(ids(&pager), [sale.id.as_str()]);
+        assert!(!pager.has_more());
+    }
+
+    #[test]
+    fn loaded_sales_show_a_renamed_partner() {
+        let (db, mut partner) = sales(1);
+        let mut pager = Pager::<SaleDetails>::default();
+        block_on(pager.refresh(&db, vec![])).unwrap();
+
+        partner.partner_name = "Паркет 30".into();
+        block_on(partner.update(&db)).unwrap();
+        block_on(pager.refresh(&db, vec![])).unwrap();
+
+        assert_eq!(pager.items()[0].partner_name, "Паркет 30");
+    }
+}
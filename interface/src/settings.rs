@@ -0,0 +1,130 @@
+//! Appearance chosen on the settings screen: theme, scale and font size.
+//! Kept in the eframe storage and applied to the egui context.
+
+use std::ops::RangeInclusive;
+
+use eframe::egui::{self, style::default_text_styles, Color32, Rounding, Stroke, Theme, ThemePreference};
+use serde::{Deserialize, Serialize};
+
+/// Accent color of the company, used by both themes.
+pub const BRAND_GREEN: &str = "#67ba80";
+/// The accent under the light text of the dark theme, which is hardly
+/// readable on the brand green itself.
+const BRAND_GREEN_DARK: &str = "#2f6b44";
+
+pub const SCALES: RangeInclusive<f32> = 0.75..=2.0;
+pub const FONT_SIZES: RangeInclusive<f32> = 10.0..=24.0;
+
+/// Size of the body text egui starts with, which [`Settings::font_size`] is relative to.
+const DEFAULT_FONT_SIZE: f32 = 12.5;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub theme: ThemePreference,
+    /// Zoom of the whole interface, 1.0 is the native size.
+    pub scale: f32,
+    /// Size of the body text in points before scaling; other text styles follow it.
+    pub font_size: f32,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Settings {
+            theme: ThemePreference::Light,
+            scale: 1.0,
+            font_size: DEFAULT_FONT_SIZE,
+        }
+    }
+}
+
+impl Settings {
+    pub fn apply(&self, ctx: &egui::Context) {
+        ctx.set_theme(self.theme);
+        // The scale is only changed here, so that the stored one stays in effect
+        ctx.options_mut(|options| options.zoom_with_keyboard = false);
+        ctx.set_zoom_factor(self.scale.clamp(*SCALES.start(), *SCALES.end()));
+
+        let ratio = self.font_size.clamp(*FONT_SIZES.start(), *FONT_SIZES.end()) / DEFAULT_FONT_SIZE;
+        let defaults = default_text_styles();
+        ctx.all_styles_mut(|style| {
+            style.text_styles.iter_mut().for_each(|(text_style, font)| {
+                if let Some(default) = defaults.get(text_style) {
+                    font.size = default.size * ratio;
+                }
+            });
+        });
+    }
+}
+
+/// Installs the light and dark visuals with the brand accents, so switching
+/// the theme keeps the look of the application.
+pub fn install_visuals(ctx: &egui::Context) {
+    ctx.set_visuals_of(Theme::Light, visuals(Theme::Light));
+    ctx.set_visuals_of(Theme::Dark, visuals(Theme::Dark));
+}
+
+fn visuals(theme: Theme) -> egui::Visuals {
+    let (base, widgets, text, green) = match theme {
+        Theme::Light => (egui::Visuals::light(), egui::style::Widgets::light(), Color32::from_gray(60), BRAND_GREEN),
+        Theme::Dark => (egui::Visuals::dark(), egui::style::Widgets::dark(), Color32::from_gray(240), BRAND_GREEN_DARK),
+    };
+    let green = Color32::from_hex(green).unwrap_or(Color32::DARK_GREEN);
+
+    egui::Visuals {
+        widgets: egui::style::Widgets {
+            inactive: egui::style::WidgetVisuals {
+                weak_bg_fill: green,
+                bg_fill: widgets.inactive.bg_fill,
+                bg_stroke: Default::default(), // separators, indentation lines
+                fg_stroke: Stroke::new(1.0, text), // normal text color
+                rounding: Rounding::same(2.0),
+                expansion: 0.0,
+            },
+            hovered: egui::style::WidgetVisuals {
+                weak_bg_fill: Color32::DARK_GREEN,
+                bg_fill: Color32::DARK_GREEN,
+                bg_stroke: Stroke::new(1.0, Color32::from_gray(105)), // e.g. hover over window edge or button
+                fg_stroke: Stroke::new(1.5, Color32::from_gray(240)),
+                rounding: Rounding::same(3.0),
+                expansion: 1.0,
+            },
+            ..widgets
+        },
+        selection: egui::style::Selection {
+            bg_fill: green.gamma_multiply(0.6),
+            stroke: Stroke::new(1.0, text),
+        },
+        ..base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Relative luminance of WCAG 2.
+    fn luminance(color: Color32) -> f32 {
+        let channel = |c: u8| {
+            let c = c as f32 / 255.0;
+            if c <= 0.03928 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+        };
+        0.2126 * channel(color.r()) + 0.7152 * channel(color.g()) + 0.0722 * channel(color.b())
+    }
+
+    fn contrast(a: Color32, b: Color32) -> f32 {
+        let (a, b) = (luminance(a), luminance(b));
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    #[test]
+    fn button_text_is_readable_in_both_themes() {
+        for theme in [Theme::Light, Theme::Dark] {
+            let widgets = visuals(theme).widgets;
+            for state in [widgets.inactive, widgets.hovered] {
+                let ratio = contrast(state.fg_stroke.color, state.weak_bg_fill);
+                assert!(ratio >= 4.5, "{:?}: contrast {:.1}", theme, ratio);
+            }
+        }
+    }
+}
@@ -0,0 +1,410 @@
+//! The views run headless on an in-memory database: frames of [`views::show`]
+//! without a window or a GPU, clicked and typed into through the AccessKit
+//! tree egui builds for screen readers, which names every widget and knows
+//! where it is.
+
+use std::collections::HashMap;
+
+use demo_core::models::address::Address;
+use demo_core::models::partners::Partner;
+use demo_core::models::product_types::ProductType;
+use demo_core::models::products::Product;
+use demo_core::models::repository::Repository;
+use demo_core::models::sales::Sale;
+use demo_core::{Error, Rule};
+use eframe::egui::{self, accesskit, Event, Key, Modifiers, Order, Pos2, Rect};
+use futures::executor::block_on;
+use sqlx::SqlitePool;
+
+use crate::i18n::{self, tr};
+use crate::views::{self, Views};
+use crate::{History, MyEguiApp};
+
+const SCREEN: Rect = Rect::from_min_max(Pos2::ZERO, Pos2::new(1280.0, 800.0));
+/// Frames run after each input: a window takes one to size itself and
+/// another to show, a combo box one to open its popup.
+const FRAMES: usize = 3;
+
+struct Harness {
+    ctx: egui::Context,
+    app: MyEguiApp,
+    time: f64,
+    /// Widgets of the last frame, in the order they were added.
+    widgets: Vec<accesskit::Node>,
+}
+
+impl Harness {
+    /// The application on a fresh in-memory database, on the main menu.
+    fn new() -> Self {
+        let db = block_on(demo_core::test_util::connect_in_memory()).unwrap();
+
+        let ctx = egui::Context::default();
+        ctx.enable_accesskit();
+        crate::settings::install_visuals(&ctx);
+        let settings = crate::settings::Settings::default();
+        settings.apply(&ctx);
+        ctx.style_mut(|style| style.animation_time = 0.0);
+
+        let app = MyEguiApp {
+            db,
+            history: History::default(),
+            current_view: Views::MainView,
+            view_data: Default::default(),
+            notifications: Default::default(),
+            palette: Default::default(),
+            settings,
+            sales_presets: vec![],
+        };
+
+        let mut harness = Harness { ctx, app, time: 0.0, widgets: vec![] };
+        harness.run(vec![]);
+        harness
+    }
+
+    fn db(&self) -> &SqlitePool {
+        &self.app.db
+    }
+
+    /// Feeds `events` to the first frame, then lets the views settle.
+    fn run(&mut self, events: Vec<Event>) {
+        let mut events = Some(events);
+        for _ in 0..FRAMES {
+            self.time += 1.0 / 60.0;
+            let input = egui::RawInput {
+                screen_rect: Some(SCREEN),
+                time: Some(self.time),
+                events: events.take().unwrap_or_default(),
+                ..Default::default()
+            };
+            let app = &mut self.app;
+            let output = self.ctx.run(input, |ctx| views::show(app, ctx));
+
+            if let Some(update) = output.platform_output.accesskit_update {
+                let root = update.tree.map(|tree| tree.root).unwrap_or(update.focus);
+                self.widgets = in_order(root, update.nodes.into_iter().collect());
+            }
+        }
+    }
+
+    /// The only widget named `name` whose centre lies on a layer that `on`
+    /// accepts, i.e. where a click on it would land.
+    fn find(&self, name: &str, on: impl Fn(egui::LayerId) -> bool) -> Rect {
+        let found: Vec<Rect> = self
+            .widgets
+            .iter()
+            .filter(|node| node.name() == Some(name))
+            .filter_map(|node| node.bounds().map(rect))
+            .filter(|rect| self.ctx.layer_id_at(rect.center()).is_some_and(&on))
+            .collect();
+        match found[..] {
+            [rect] => rect,
+            [] => panic!("No «{}» among {:?}", name, self.names()),
+            _ => panic!("{} widgets named «{}»", found.len(), name),
+        }
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.widgets.iter().filter_map(|node| node.name()).collect()
+    }
+
+    /// Whether a widget shows exactly `text`, e.g. a toast.
+    fn shows(&self, text: &str) -> bool {
+        self.widgets.iter().any(|node| node.name() == Some(text))
+    }
+
+    fn click_at(&mut self, pos: Pos2) {
+        let button = |pressed| Event::PointerButton {
+            pos,
+            button: egui::PointerButton::Primary,
+            pressed,
+            modifiers: Modifiers::NONE,
+        };
+        self.run(vec![Event::PointerMoved(pos), button(true)]);
+        self.run(vec![button(false)]);
+    }
+
+    /// Clicks the button named `name` outside of the windows, e.g. in a side panel.
+    fn click(&mut self, name: &str) {
+        let rect = self.find(name, |layer| layer.order == Order::Background);
+        self.click_at(rect.center());
+    }
+
+    fn type_text(&mut self, text: &str) {
+        self.run(vec![Event::Text(text.to_string())]);
+    }
+
+    fn press(&mut self, key: Key) {
+        let event = |pressed| Event::Key { key, physical_key: None, pressed, repeat: false, modifiers: Modifiers::NONE };
+        self.run(vec![event(true), event(false)]);
+    }
+
+    fn window(&mut self, title: &str) -> Window<'_> {
+        let layer = egui::LayerId::new(Order::Middle, egui::Id::new(title));
+        assert!(
+            self.ctx.memory(|memory| memory.areas().is_visible(&layer)),
+            "No window «{}»",
+            title
+        );
+        Window { harness: self, layer }
+    }
+}
+
+/// A window of the views, found by its title.
+struct Window<'a> {
+    harness: &'a mut Harness,
+    layer: egui::LayerId,
+}
+
+impl Window<'_> {
+    fn find(&self, name: &str) -> Rect {
+        self.harness.find(name, |layer| layer == self.layer)
+    }
+
+    /// The editor following the label `label`, as [`FormView`](crate::widgets::FormView) lays them out.
+    fn field(&self, label: &str) -> Rect {
+        let label_rect = self.find(label);
+        let widgets = &self.harness.widgets;
+        let label_index = widgets
+            .iter()
+            .position(|node| node.name() == Some(label) && node.bounds().map(rect) == Some(label_rect))
+            .unwrap();
+        widgets[label_index + 1..]
+            .iter()
+            .find(|node| {
+                matches!(
+                    node.role(),
+                    accesskit::Role::TextInput | accesskit::Role::SpinButton | accesskit::Role::ComboBox
+                )
+            })
+            .and_then(|node| node.bounds().map(rect))
+            .unwrap_or_else(|| panic!("No editor after «{}»", label))
+    }
+
+    fn fill(self, label: &str, text: &str) -> Self {
+        let field = self.field(label);
+        self.harness.click_at(field.center());
+        self.harness.type_text(text);
+        self
+    }
+
+    fn number(self, label: &str, value: i64) -> Self {
+        let window = self.fill(label, &value.to_string());
+        window.harness.press(Key::Tab);
+        window
+    }
+
+    /// Picks `option` in the combo box following `label`.
+    fn choose(self, label: &str, option: &str) -> Self {
+        let field = self.field(label);
+        self.harness.click_at(field.center());
+        let option = self.harness.find(option, |layer| layer.order == Order::Foreground);
+        self.harness.click_at(option.center());
+        self
+    }
+
+    fn click(self, name: &str) -> Self {
+        let rect = self.find(name);
+        self.harness.click_at(rect.center());
+        self
+    }
+}
+
+/// Nodes of the tree from `root` down, depth first, which is the order egui added them in.
+fn in_order(root: accesskit::NodeId, mut nodes: HashMap<accesskit::NodeId, accesskit::Node>) -> Vec<accesskit::Node> {
+    let mut ordered = vec![];
+    let mut stack = vec![root];
+    while let Some(id) = stack.pop() {
+        if let Some(node) = nodes.remove(&id) {
+            stack.extend(node.children().iter().rev());
+            ordered.push(node);
+        }
+    }
+    ordered
+}
+
+fn rect(bounds: accesskit::Rect) -> Rect {
+    Rect::from_min_max(
+        Pos2::new(bounds.x0 as f32, bounds.y0 as f32),
+        Pos2::new(bounds.x1 as f32, bounds.y1 as f32),
+    )
+}
+
+/// A partner and a product to sell, added through the models.
+fn catalog(db: &SqlitePool) -> (Partner, Product) {
+    let partner = Partner::new(
+        "ООО".into(),
+        "Паркет 29".into(),
+        "Петров Василий Петрович".into(),
+        "vppetrov@vl.ru".into(),
+        "987 123 56 78".into(),
+        Address::parse("164500, Архангельская область, город Северодвинск, ул. Строителей, 18").unwrap(),
+        "3333888520".into(),
+        7,
+    );
+    block_on(partner.insert(db)).unwrap();
+    block_on(ProductType::new("Ламинат".into(), 2.35).insert(db)).unwrap();
+    let product = Product::new("Ламинат".into(), "Ламинат Дуб 12 мм".into(), "7750282".into(), 1799);
+    block_on(product.insert(db)).unwrap();
+
+    (partner, product)
+}
+
+#[test]
+fn partner_form_adds_a_partner() {
+    let mut harness = Harness::new();
+    harness.click(&tr!("partners"));
+    harness.click(&tr!("add"));
+
+    harness
+        .window(&tr!("partner"))
+        .fill(&tr!("organization-name"), "Стройсервис")
+        .choose(&tr!("organization-type"), "ЗАО")
+        .fill(&tr!("organization-director"), "Соловьев Андрей Николаевич")
+        .fill(&tr!("email"), "ansolovev@st.ru")
+        .fill(&tr!("contact-phone"), "812 223 32 00")
+        .fill(&tr!("postal-index"), "188910")
+        .fill(&tr!("region"), "Ленинградская область")
+        .fill(&tr!("city"), "Приморск")
+        .fill(&tr!("street"), "ул. Парковая")
+        .fill(&tr!("building"), "21")
+        .fill(&tr!("inn"), "4440391029")
+        .number(&tr!("rating"), 7)
+        .click(&tr!("submit"));
+
+    let partners = block_on(Partner::list(harness.db(), &[], None)).unwrap();
+    assert_eq!(partners.len(), 1);
+    let partner = &partners[0];
+    assert_eq!(
+        (partner.partner_type.as_str(), partner.partner_name.as_str(), partner.rating),
+        ("ЗАО", "Стройсервис", 7)
+    );
+    assert_eq!(partner.legal_address, "188910, Ленинградская область, Приморск, ул. Парковая, 21");
+    assert!(harness.shows(&tr!("partner-created")));
+}
+
+#[test]
+fn partner_form_is_not_submitted_with_a_wrong_postal_index() {
+    let mut harness = Harness::new();
+    harness.click(&tr!("partners"));
+    harness.click(&tr!("add"));
+
+    harness
+        .window(&tr!("partner"))
+        .fill(&tr!("organization-name"), "Стройсервис")
+        .choose(&tr!("organization-type"), "ЗАО")
+        .fill(&tr!("postal-index"), "18891")
+        .fill(&tr!("region"), "Ленинградская область")
+        .fill(&tr!("city"), "Приморск")
+        .fill(&tr!("street"), "ул. Парковая")
+        .fill(&tr!("building"), "21")
+        .click(&tr!("submit"));
+    let field = ["«", &tr!("field-postal_index"), "»"].concat();
+    assert!(harness.names().iter().any(|name| name.contains(&field)));
+    assert_eq!(block_on(Partner::count(harness.db(), &[])).unwrap(), 0);
+
+    harness
+        .window(&tr!("partner"))
+        .fill(&tr!("postal-index"), "188910")
+        .click(&tr!("submit"));
+    assert_eq!(block_on(Partner::count(harness.db(), &[])).unwrap(), 1);
+}
+
+#[test]
+fn product_form_adds_a_product() {
+    let mut harness = Harness::new();
+    block_on(ProductType::new("Ламинат".into(), 2.35).insert(harness.db())).unwrap();
+    harness.click(&tr!("goods"));
+    harness.click(&tr!("add"));
+
+    harness
+        .window(&tr!("product"))
+        .choose(&tr!("type"), "Ламинат")
+        .fill(&tr!("name"), "Ламинат Дуб 12 мм")
+        .fill(&tr!("article-number"), "7750282")
+        .number(&tr!("minimum-cost"), 1799)
+        .click(&tr!("submit"));
+
+    let products = block_on(Product::list(harness.db(), &[], None)).unwrap();
+    assert_eq!(products.len(), 1);
+    assert_eq!(
+        (products[0].product_type.as_str(), products[0].product_name.as_str(), products[0].minimum_cost),
+        ("Ламинат", "Ламинат Дуб 12 мм", 1799)
+    );
+    assert!(harness.shows(&tr!("record-submitted", form = tr!("product"))));
+}
+
+#[test]
+fn duplicate_product_type_is_reported_and_the_form_stays_open() {
+    let mut harness = Harness::new();
+    block_on(ProductType::new("Ламинат".into(), 2.35).insert(harness.db())).unwrap();
+    harness.click(&tr!("goods"));
+    harness.click(&tr!("new-type"));
+
+    harness
+        .window(&tr!("product-type"))
+        .fill(&tr!("name"), "Ламинат")
+        .number(&tr!("coefficient"), 2)
+        .click(&tr!("submit"));
+
+    let duplicate = Error::UniqueViolation {
+        table: "product_types".into(),
+        columns: vec!["product_type".into()],
+        source: None,
+    };
+    assert!(harness.shows(&i18n::error_message(&duplicate)));
+    harness.window(&tr!("product-type"));
+    assert_eq!(block_on(ProductType::list(harness.db(), &[], None)).unwrap().len(), 1);
+}
+
+#[test]
+fn sale_form_adds_a_sale() {
+    let mut harness = Harness::new();
+    let (partner, product) = catalog(harness.db());
+    harness.click(&tr!("sales"));
+    harness.click(&tr!("add"));
+
+    harness
+        .window(&tr!("sale"))
+        .choose(&tr!("product"), "Ламинат Дуб 12 мм | Ламинат")
+        .number(&tr!("quantity"), 300)
+        .choose(&tr!("partner"), "Паркет 29 | ООО")
+        .click(&tr!("submit"));
+
+    let sales = block_on(Sale::list(harness.db(), &[], None)).unwrap();
+    assert_eq!(sales.len(), 1);
+    assert_eq!(
+        (sales[0].product_id.as_str(), sales[0].partner_id.as_str(), sales[0].quantity),
+        (product.id.as_str(), partner.id.as_str(), 300)
+    );
+    assert!(harness.shows(&tr!("record-submitted", form = tr!("sale"))));
+    assert!(!harness.ctx.memory(|memory| memory
+        .areas()
+        .is_visible(&egui::LayerId::new(Order::Middle, egui::Id::new(tr!("sale"))))));
+}
+
+#[test]
+fn sale_form_is_not_submitted_until_it_is_complete() {
+    let mut harness = Harness::new();
+    catalog(harness.db());
+    harness.click(&tr!("sales"));
+    harness.click(&tr!("add"));
+
+    let product_required = i18n::error_message(&Error::validation("sales", "product_id", Rule::Required));
+    let quantity_positive = i18n::error_message(&Error::validation("sales", "quantity", Rule::Positive));
+    harness.window(&tr!("sale")).click(&tr!("submit"));
+    assert!(harness.shows(&product_required));
+
+    harness
+        .window(&tr!("sale"))
+        .choose(&tr!("product"), "Ламинат Дуб 12 мм | Ламинат")
+        .choose(&tr!("partner"), "Паркет 29 | ООО")
+        .click(&tr!("submit"));
+    assert!(harness.shows(&quantity_positive));
+
+    harness.window(&tr!("sale")).number(&tr!("quantity"), 300);
+    assert!(!harness.shows(&quantity_positive));
+    assert_eq!(block_on(Sale::count(harness.db(), &[])).unwrap(), 0);
+
+    harness.window(&tr!("sale")).click(&tr!("submit"));
+    assert_eq!(block_on(Sale::count(harness.db(), &[])).unwrap(), 1);
+}
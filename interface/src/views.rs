@@ -1,48 +1,134 @@
-use crate::models::partners;
-use crate::models::product_types;
-use crate::models::product_types::ProductType;
-use crate::models::products;
-use crate::models::products::Product;
-use crate::models::sales;
-use crate::models::sales::Sale;
-use chrono::Datelike;
+use demo_core::models::address::Address;
+use demo_core::models::documents::DocumentType;
+use demo_core::models::duplicates;
+use demo_core::models::duplicates::{Duplicate, PartnerField};
+use demo_core::models::materials;
+use demo_core::models::materials::Material;
+use demo_core::models::partner_rating_history;
+use demo_core::models::partner_rating_history::RatingChange;
+use demo_core::models::partner_types;
+use demo_core::models::partner_types::{PartnerType, TypeMapping};
+use demo_core::models::partners;
+use demo_core::models::partners::UnparsedAddress;
+use demo_core::models::product_materials;
+use demo_core::models::product_materials::{ProductCost, ProductMaterial};
+use demo_core::models::product_types::ProductType;
+use demo_core::models::repository::{self, Filter, Repository};
+use demo_core::models::search::{self, SearchResults};
+use demo_core::models::products::Product;
+use demo_core::models::sales::{Sale, SaleDetails, SalesFilter};
+use demo_core::models::suppliers;
+use demo_core::models::suppliers::Supplier;
 use eframe::egui::include_image;
 use eframe::egui::ComboBox;
-use eframe::egui::DragValue;
 use eframe::egui::Image;
-use eframe::egui::{self, Color32, RichText, Stroke};
+use eframe::egui::{self, Color32, RichText, ThemePreference};
 use egui_extras::DatePickerButton;
+use egui_plot::{Line, Plot, PlotPoints};
 use futures::executor::block_on;
-use sqlx::types::time::Date;
-
-use crate::{models::partners::Partner, MyEguiApp};
-
-#[derive(Clone, Copy)]
+use sqlx::SqlitePool;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use demo_core::models::partners::Partner;
+use demo_core::printing;
+use demo_core::Rule;
+use crate::commands::{self, Command, Entry};
+use crate::i18n::{self, format_date, format_number, tr, Language};
+use crate::notifications::Notifications;
+use crate::paging::{Paged, Pager};
+use crate::settings::{self, Settings};
+use crate::widgets::{panel_button, Field, FormView, ListView};
+use crate::MyEguiApp;
+
+/// Directory printed documents are saved to before opening them in the system viewer.
+const DOCUMENTS_DIR: &str = "documents";
+/// Id of the search box of the header, focused by [`commands::SEARCH`].
+const SEARCH_ID: &str = "header_search";
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Views {
     MainView,
     Partners,
     Sales,
-    Products
+    Products,
+    Materials,
+    Settings
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct PartnersForm {
-    partner_type: String,
+    partner_type: Option<String>,
     partner_name: String,
     director: String,
     email: String,
     phone: String,
-    legal_address: String,
+    address: Address,
     inn: String,
     rating: i64,
 }
 
+/// Forms and filters are kept between runs; windows showing query results
+/// are not, their data would be stale.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct PartnersViewData {
-    error: (bool, String),
     form: (bool, PartnersForm),
+    rating_form: (bool, RatingForm),
+    partner_types_form: (bool, PartnerTypesForm),
+    #[serde(skip)]
+    normalization: (bool, Vec<TypeMapping>),
+    filter: PartnersFilter,
+    #[serde(skip)]
+    unparsed_addresses: (bool, Vec<UnparsedAddress>),
+    #[serde(skip)]
+    duplicates: Vec<Duplicate>,
+    #[serde(skip)]
+    merge_form: (bool, MergeForm),
+    #[serde(skip)]
+    pager: Pager<Partner>,
+}
+
+#[derive(Default)]
+struct MergeForm {
+    pairs: Vec<(Partner, Duplicate)>,
+    survivor: Option<Partner>,
+    duplicate: Option<Partner>,
+    from_duplicate: Vec<PartnerField>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PartnersFilter {
+    region: Option<String>,
+    city: Option<String>,
+}
+
+impl PartnersFilter {
+    fn filters(&self) -> Vec<Filter> {
+        let mut filters = vec![];
+        if let Some(region) = &self.region {
+            filters.push(Filter::eq("region", region.as_str()));
+        }
+        if let Some(city) = &self.city {
+            filters.push(Filter::eq("city", city.as_str()));
+        }
+        filters
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct PartnerTypesForm {
+    partner_type: String,
 }
 
-#[derive(Clone)]
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct RatingForm {
+    partner: Option<Partner>,
+    rating: i64,
+    comment: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
 struct SalesForm {
     product: Option<Product>,
     quantity: i64,
@@ -52,81 +138,124 @@ struct SalesForm {
 
 impl Default for SalesForm {
     fn default() -> Self {
-        Self {
+        SalesForm {
             product: None,
             quantity: 0,
-            sale_date: chrono::NaiveDate::default(),
+            sale_date: chrono::Local::now().date_naive(),
             partner: None,
         }
     }
 }
 
+impl SalesForm {
+    /// The sale the form describes, or what it is missing.
+    fn sale(&self) -> demo_core::Result<Sale> {
+        let product = self.product.as_ref().ok_or_else(|| demo_core::Error::validation("sales", "product_id", Rule::Required))?;
+        let partner = self.partner.as_ref().ok_or_else(|| demo_core::Error::validation("sales", "partner_id", Rule::Required))?;
+        let sale = Sale::new(product.id.clone(), self.quantity, self.sale_date, partner.id.clone());
+        sale.validate()?;
+        Ok(sale)
+    }
+
+    /// A blank form for the next sale, to the same partner as this one.
+    fn next(&self) -> Self {
+        SalesForm { partner: self.partner.clone(), ..Default::default() }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
 struct SalesViewData {
-    error: (bool, String),
     form: (bool, SalesForm),
+    filter: SalesFilter,
+    /// Name to save the filter under as a [`SalesPreset`].
+    preset_name: String,
+    #[serde(skip)]
+    pager: Pager<SaleDetails>,
+    #[serde(skip)]
+    choices: SalesChoices,
 }
 
-pub struct ViewsData {
-    partners_view: PartnersViewData,
-    sales_views: SalesViewData,
-    products_view: ProductsViewData
+/// Partners, products and product types offered by the sales form and
+/// filters, loaded again only once one of their tables has changed.
+#[derive(Default)]
+struct SalesChoices {
+    /// [`Repository::version`] of the three tables when they were loaded.
+    versions: Option<[i64; 3]>,
+    partners: Vec<Partner>,
+    products: Vec<Product>,
+    product_types: Vec<ProductType>,
 }
 
-impl Default for ViewsData {
-    fn default() -> Self {
-        ViewsData {
-            partners_view: PartnersViewData {
-                error: (false, "".to_string()),
-                form: (false, PartnersForm::default()),
-            },
-            sales_views: SalesViewData {
-                error: (false, "".to_string()),
-                form: (false, SalesForm::default()),
-            },
-            products_view: ProductsViewData::default()
+impl SalesChoices {
+    fn refresh(&mut self, db: &SqlitePool, notifications: &mut Notifications) {
+        let mut refresh = || -> demo_core::Result<()> {
+            let versions = [
+                block_on(Partner::version(db))?,
+                block_on(Product::version(db))?,
+                block_on(ProductType::version(db))?,
+            ];
+            if self.versions != Some(versions) {
+                *self = SalesChoices {
+                    versions: Some(versions),
+                    partners: block_on(Partner::list(db, &[], None))?,
+                    products: block_on(Product::list(db, &[], None))?,
+                    product_types: block_on(ProductType::list(db, &[], None))?,
+                };
+            }
+            Ok(())
+        };
+
+        if let Err(e) = refresh() {
+            notifications.error(&e);
         }
     }
 }
 
-impl Default for PartnersForm {
-    fn default() -> Self {
-        Self {
-            partner_type: "".to_string(),
-            partner_name: "".to_string(),
-            director: "".to_string(),
-            email: "".to_string(),
-            phone: "".to_string(),
-            legal_address: "".to_string(),
-            inn: "".to_string(),
-            rating: 0,
-        }
-    }
+/// A sales filter saved under a name, e.g. "Паркет 29 за квартал". Kept
+/// apart from [`ViewsData`], so discarding the drafts keeps the presets.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SalesPreset {
+    name: String,
+    filter: SalesFilter,
 }
 
-struct ProductsViewData {
-    error: (bool, String),
-    products_form: (bool, ProductsForm),
-    product_types_form: (bool, ProductTypesForm)
+/// State of the views: open windows, form drafts and filters. Saved with
+/// the eframe storage, so a restart continues where the user left off.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewsData {
+    partners_view: PartnersViewData,
+    sales_views: SalesViewData,
+    products_view: ProductsViewData,
+    materials_view: MaterialsViewData,
+    /// Id of the record chosen in the command palette, to scroll to once.
+    #[serde(skip)]
+    reveal: Option<String>,
+    #[serde(skip)]
+    search: HeaderSearch,
 }
 
-impl Default for ProductsViewData {
-    fn default() -> Self {
-        Self {
-            error: (false,"".to_string()),
-            products_form: (false, ProductsForm {
-                product_type: None,
-                product_name: "".to_string(),
-                article_number: "".to_string(),
-                minimum_cost: 0
-            }),
-            product_types_form: (false, ProductTypesForm {
-                product_type: "".to_string(),
-                coefficient: 0.0
-            })
-        }
-    }
+/// The search box of the header and the results of its text.
+#[derive(Default)]
+struct HeaderSearch {
+    query: String,
+    /// Text the results are for; the search runs again when the query differs.
+    searched: String,
+    results: SearchResults,
+    open: bool,
 }
 
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct ProductsViewData {
+    products_form: (bool, ProductsForm),
+    product_types_form: (bool, ProductTypesForm),
+    #[serde(skip)]
+    pager: Pager<Product>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
 struct ProductsForm {
     product_type: Option<ProductType>,
     product_name: String,
@@ -134,38 +263,368 @@ struct ProductsForm {
     minimum_cost: i64,
 }
 
+impl ProductsForm {
+    /// The product the form describes, or what it is missing.
+    fn product(&self) -> demo_core::Result<Product> {
+        let product_type = self.product_type.as_ref().ok_or_else(|| demo_core::Error::validation("products", "product_type", Rule::Required))?;
+        Ok(Product::new(
+            product_type.product_type.clone(),
+            self.product_name.clone(),
+            self.article_number.clone(),
+            self.minimum_cost,
+        ))
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
 struct ProductTypesForm {
     product_type: String,
     coefficient: f64
 }
 
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct MaterialsViewData {
+    materials_form: (bool, MaterialsForm),
+    suppliers_form: (bool, SuppliersForm),
+    product_materials_form: (bool, ProductMaterialsForm),
+    #[serde(skip)]
+    lists: MaterialsLists,
+}
+
+/// Records the Materials view shows and offers, loaded again only once one
+/// of their tables has changed.
+#[derive(Default)]
+struct MaterialsLists {
+    /// [`repository::version`] of the tables when they were loaded.
+    versions: Option<[i64; 4]>,
+    materials: Vec<Material>,
+    suppliers: Vec<Supplier>,
+    products: Vec<Product>,
+    product_materials: Vec<ProductMaterial>,
+    costs: Vec<ProductCost>,
+}
+
+impl MaterialsLists {
+    fn refresh(&mut self, db: &SqlitePool, notifications: &mut Notifications) {
+        let mut refresh = || -> demo_core::Result<()> {
+            let versions = [
+                block_on(repository::version(db, "materials"))?,
+                block_on(repository::version(db, "suppliers"))?,
+                block_on(Product::version(db))?,
+                block_on(repository::version(db, "product_materials"))?,
+            ];
+            if self.versions != Some(versions) {
+                *self = MaterialsLists {
+                    versions: Some(versions),
+                    materials: block_on(materials::get_all(db))?,
+                    suppliers: block_on(suppliers::get_all(db))?,
+                    products: block_on(Product::list(db, &[], None))?,
+                    product_materials: block_on(product_materials::get_all(db))?,
+                    costs: block_on(product_materials::get_costs(db))?,
+                };
+            }
+            Ok(())
+        };
+
+        if let Err(e) = refresh() {
+            notifications.error(&e);
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct MaterialsForm {
+    material_name: String,
+    unit: String,
+    price: f64,
+    defect_percentage: f64,
+    supplier: Option<Supplier>,
+}
+
+impl MaterialsForm {
+    /// The material the form describes, or what it is missing.
+    fn material(&self) -> demo_core::Result<Material> {
+        let supplier = self.supplier.as_ref().ok_or_else(|| demo_core::Error::validation("materials", "supplier_id", Rule::Required))?;
+        Ok(Material::new(
+            self.material_name.clone(),
+            self.unit.clone(),
+            self.price,
+            self.defect_percentage,
+            supplier.id.clone(),
+        ))
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct SuppliersForm {
+    supplier_type: String,
+    supplier_name: String,
+    inn: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+struct ProductMaterialsForm {
+    product: Option<Product>,
+    material: Option<Material>,
+    quantity: f64,
+}
+
+impl ProductMaterialsForm {
+    /// The bill of materials entry the form describes, or what it is missing.
+    fn product_material(&self) -> demo_core::Result<ProductMaterial> {
+        let product = self.product.as_ref().ok_or_else(|| demo_core::Error::validation("product_materials", "product_id", Rule::Required))?;
+        let material = self.material.as_ref().ok_or_else(|| demo_core::Error::validation("product_materials", "material_id", Rule::Required))?;
+        if self.quantity <= 0.0 {
+            return Err(demo_core::Error::validation("product_materials", "quantity", Rule::Positive));
+        }
+        Ok(ProductMaterial::new(product.id.clone(), material.id.clone(), self.quantity))
+    }
+}
+
+/// The loaded values, or none with the error reported.
+fn or_report<T>(result: demo_core::Result<Vec<T>>, notifications: &mut Notifications) -> Vec<T> {
+    result.unwrap_or_else(|e| {
+        notifications.error(&e);
+        vec![]
+    })
+}
+
+/// Brings the pager of a view up to date with `filters`, loading as far as
+/// the record to reveal.
+fn refresh<T: Paged>(
+    db: &SqlitePool,
+    pager: &mut Pager<T>,
+    filters: Vec<Filter>,
+    reveal: &Option<String>,
+    id: impl Fn(&T) -> &String,
+    notifications: &mut Notifications,
+) {
+    let refresh = || -> demo_core::Result<()> {
+        block_on(pager.refresh(db, filters))?;
+        if let Some(reveal) = reveal {
+            block_on(pager.load_until(db, |item| id(item) == reveal))?;
+        }
+        Ok(())
+    };
+
+    if let Err(e) = refresh() {
+        notifications.error(&e);
+    }
+}
+
+/// Does what the user asked for by a shortcut or in the command palette.
+fn execute(app: &mut MyEguiApp, command: Command) {
+    match command {
+        Command::Back => app.go_back(),
+        Command::Forward => app.go_forward(),
+        Command::Open(view) => {
+            if app.current_view != view {
+                app.set_view(view);
+            }
+        }
+        Command::NewRecord => match app.current_view {
+            Views::Partners => app.view_data.partners_view.form.0 = true,
+            Views::Sales => app.view_data.sales_views.form.0 = true,
+            Views::Products => app.view_data.products_view.products_form.0 = true,
+            Views::Materials => app.view_data.materials_view.materials_form.0 = true,
+            Views::MainView | Views::Settings => {}
+        },
+        Command::Reveal(view, id) => {
+            execute(app, Command::Open(view));
+            // A filtered out record could not be shown
+            match view {
+                Views::Partners => app.view_data.partners_view.filter = PartnersFilter::default(),
+                Views::Sales => app.view_data.sales_views.filter = SalesFilter::default(),
+                Views::Products | Views::Materials | Views::MainView | Views::Settings => {}
+            }
+            app.view_data.reveal = Some(id);
+        }
+    }
+}
+
+/// The search box of the header. Its results drop down below it, grouped by
+/// entity; choosing one opens the record in its view.
+fn header_search(app: &mut MyEguiApp, ui: &mut egui::Ui) -> Option<Command> {
+    let search = &mut app.view_data.search;
+    let field = ui.add(
+        egui::TextEdit::singleline(&mut search.query)
+            .id(egui::Id::new(SEARCH_ID))
+            .hint_text(tr!("search-hint", shortcut = ui.ctx().format_shortcut(&commands::SEARCH)))
+            .desired_width(250.0),
+    );
+    if field.changed() || field.gained_focus() {
+        search.open = !search.query.trim().is_empty();
+    }
+    if search.query != search.searched {
+        search.results = block_on(search::search(&app.db, &search.query)).unwrap_or_else(|e| {
+            app.notifications.error(&e);
+            SearchResults::default()
+        });
+        search.searched = search.query.clone();
+    }
+    if !search.open {
+        return None;
+    }
+
+    let results = &search.results;
+    let mut command = None;
+    let popup = egui::Area::new(egui::Id::new("search_results"))
+        .order(egui::Order::Foreground)
+        .fixed_pos(field.rect.left_bottom())
+        .show(ui.ctx(), |ui| {
+            egui::Frame::popup(ui.style()).show(ui, |ui| {
+                ui.set_width(400.0);
+                egui::ScrollArea::vertical().max_height(400.0).show(ui, |ui| {
+                    if results.is_empty() {
+                        ui.label(tr!("nothing-found"));
+                    }
+                    search_group(ui, tr!("partners"), &results.partners, &mut command, |partner| {
+                        (
+                            [&partner.partner_name, " | ", &partner.inn].concat(),
+                            Command::Reveal(Views::Partners, partner.id.clone()),
+                        )
+                    });
+                    search_group(ui, tr!("products"), &results.products, &mut command, |product| {
+                        (
+                            [&product.product_name, " | ", &product.article_number].concat(),
+                            Command::Reveal(Views::Products, product.id.clone()),
+                        )
+                    });
+                    search_group(ui, tr!("sales"), &results.sales, &mut command, |sale| {
+                        (
+                            tr!(
+                                "found-sale",
+                                product = sale.product_name.as_str(),
+                                partner = sale.partner_name.as_str(),
+                                quantity = sale.quantity,
+                                date = format_date(sale.sale_date),
+                            ),
+                            Command::Reveal(Views::Sales, sale.id.clone()),
+                        )
+                    });
+                });
+            });
+        })
+        .response;
+
+    let escape = ui.input(|i| i.key_pressed(egui::Key::Escape));
+    let clicked_outside = ui.input(|i| i.pointer.any_click()) && !popup.contains_pointer() && !field.contains_pointer();
+    if command.is_some() || escape || clicked_outside {
+        search.open = false;
+    }
+    command
+}
+
+/// Results of one entity under its heading; `entry` gives the text of a result
+/// and what choosing it does.
+fn search_group<T>(
+    ui: &mut egui::Ui,
+    heading: String,
+    items: &[T],
+    chosen: &mut Option<Command>,
+    entry: impl Fn(&T) -> (String, Command),
+) {
+    if items.is_empty() {
+        return;
+    }
+
+    ui.label(RichText::new(heading).strong());
+    items.iter().for_each(|item| {
+        let (text, command) = entry(item);
+        if ui.selectable_label(false, text).clicked() {
+            *chosen = Some(command);
+        }
+    });
+    ui.separator();
+}
+
+/// Actions of the application and the records to jump to, for the command palette.
+fn palette_entries(app: &mut MyEguiApp) -> Vec<Entry> {
+    let mut entries = vec![
+        Entry::action(tr!("main-menu"), None, Command::Open(Views::MainView)),
+        Entry::action(tr!("partners"), None, Command::Open(Views::Partners)),
+        Entry::action(tr!("sales"), None, Command::Open(Views::Sales)),
+        Entry::action(tr!("products"), None, Command::Open(Views::Products)),
+        Entry::action(tr!("materials"), None, Command::Open(Views::Materials)),
+        Entry::action(tr!("settings"), None, Command::Open(Views::Settings)),
+        Entry::action(tr!("go-back"), Some(commands::BACK), Command::Back),
+        Entry::action(tr!("go-forward"), Some(commands::FORWARD), Command::Forward),
+    ];
+    if !matches!(app.current_view, Views::MainView | Views::Settings) {
+        entries.push(Entry::action(tr!("new-record"), Some(commands::NEW_RECORD), Command::NewRecord));
+    }
+
+    let notifications = &mut app.notifications;
+    or_report(block_on(Partner::list(&app.db, &[], None)), notifications)
+        .into_iter()
+        .for_each(|partner| {
+            entries.push(Entry::record(partner.partner_name, tr!("partner"), Command::Reveal(Views::Partners, partner.id)))
+        });
+    or_report(block_on(Product::list(&app.db, &[], None)), notifications)
+        .into_iter()
+        .for_each(|product| {
+            entries.push(Entry::record(product.product_name, tr!("product"), Command::Reveal(Views::Products, product.id)))
+        });
+    or_report(block_on(materials::get_all(&app.db)), notifications)
+        .into_iter()
+        .for_each(|material| {
+            entries.push(Entry::record(material.material_name, tr!("material"), Command::Reveal(Views::Materials, material.id)))
+        });
+
+    entries
+}
+
 pub fn show(app: &mut MyEguiApp, ctx: &egui::Context) {
-    let res = || -> crate::Result<_> {
+    let mut res = || -> demo_core::Result<_> {
         egui_extras::install_image_loaders(ctx);
 
+        let mut found = None;
         egui::TopBottomPanel::top("header").show(ctx, |ui| {
             ui.horizontal_centered(|ui| {
-                if ui.button("<").clicked() {
+                if ui.button("<").on_hover_text(ctx.format_shortcut(&commands::BACK)).clicked() {
                     app.go_back();
                 };
-                if ui.button(">").clicked() {
+                if ui.button(">").on_hover_text(ctx.format_shortcut(&commands::FORWARD)).clicked() {
                     app.go_forward();
                 };
+                found = header_search(app, ui);
+
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    language_switcher(ui, "language");
+                    ui.label(tr!("language"));
+                });
             });
         });
 
-        ctx.input(|i| {
-            if i.pointer.button_clicked(egui::PointerButton::Extra1) {
-                app.go_back();
-            }
-            if i.pointer.button_clicked(egui::PointerButton::Extra2) {
-                app.go_forward();
+        // Alt+arrows move the cursor by words in a text field
+        let typing = ctx.wants_keyboard_input();
+        let command = ctx.input_mut(|i| {
+            if i.pointer.button_clicked(egui::PointerButton::Extra1) || (!typing && i.consume_shortcut(&commands::BACK)) {
+                Some(Command::Back)
+            } else if i.pointer.button_clicked(egui::PointerButton::Extra2) || (!typing && i.consume_shortcut(&commands::FORWARD)) {
+                Some(Command::Forward)
+            } else if i.consume_shortcut(&commands::NEW_RECORD) {
+                Some(Command::NewRecord)
+            } else {
+                None
             }
         });
+        if ctx.input_mut(|i| i.consume_shortcut(&commands::PALETTE)) {
+            let entries = palette_entries(app);
+            app.palette.open(entries);
+        }
+        if ctx.input_mut(|i| i.consume_shortcut(&commands::SEARCH)) {
+            ctx.memory_mut(|memory| memory.request_focus(egui::Id::new(SEARCH_ID)));
+        }
+        let command = command.or(found).or_else(|| app.palette.show(ctx));
+        if let Some(command) = command {
+            execute(app, command);
+        }
 
         match app.current_view {
             Views::MainView => {
-                ctx.send_viewport_cmd(egui::ViewportCommand::Title("Главное меню".into()));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("main-menu")));
                 egui::CentralPanel::default().show(ctx, |ui| {
                     ui.vertical_centered(|ui| {
                         ui.add(
@@ -173,86 +632,181 @@ pub fn show(app: &mut MyEguiApp, ctx: &egui::Context) {
                                 .max_width(200.0)
                                 .max_height(200.0),
                         );
-                        if ui.button(RichText::new("Партнеры").size(20.0)).clicked() {
+                        if ui.button(RichText::new(tr!("partners")).size(20.0)).clicked() {
                             app.set_view(Views::Partners);
                         };
-                        if ui.button(RichText::new("Продажи").size(20.0)).clicked() {
+                        if ui.button(RichText::new(tr!("sales")).size(20.0)).clicked() {
                             app.set_view(Views::Sales);
                         };
-                        if ui.button(RichText::new("Товары").size(20.0)).clicked() {
+                        if ui.button(RichText::new(tr!("goods")).size(20.0)).clicked() {
                             app.set_view(Views::Products);
                         };
+                        if ui.button(RichText::new(tr!("materials")).size(20.0)).clicked() {
+                            app.set_view(Views::Materials);
+                        };
+                        if ui.button(RichText::new(tr!("settings")).size(20.0)).clicked() {
+                            app.set_view(Views::Settings);
+                        };
                     })
                 });
             } // MainView
             Views::Partners => {
-                let partners_list: Vec<Partner> = match block_on(partners::get_all(&app.db)) {
-                    Ok(values) => values,
-                    Err(e) => {
-                        app.view_data.partners_view.error.0 = true;
-                        app.view_data.partners_view.error.1 = e.to_string();
-                        vec![]
-                    }
+                let partners_view = &mut app.view_data.partners_view;
+                refresh(&app.db, &mut partners_view.pager, partners_view.filter.filters(), &app.view_data.reveal, |partner| &partner.id, &mut app.notifications);
+                // Any partner can be merged, not only the loaded ones
+                let partners_list = match app.view_data.partners_view.merge_form.0 {
+                    true => or_report(block_on(Partner::list(&app.db, &[], None)), &mut app.notifications),
+                    false => vec![],
                 };
+                let partner_types_list = or_report(
+                    block_on(partner_types::get_all(&app.db)),
+                    &mut app.notifications,
+                );
 
-                ctx.send_viewport_cmd(egui::ViewportCommand::Title("Партнеры".into()));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("partners")));
                 egui::SidePanel::right("partner_panel").show(ctx, |ui| {
-                    if ui.button(RichText::new("Добавить").size(20.0)).clicked() {
+                    if panel_button(ui, tr!("add")) {
                         app.view_data.partners_view.form.0 = true
                     };
+                    if panel_button(ui, tr!("partner-types")) {
+                        app.view_data.partners_view.partner_types_form.0 = true
+                    };
+                    if panel_button(ui, tr!("duplicates")) {
+                        match block_on(duplicates::find_all_duplicates(&app.db)) {
+                            Ok(pairs) => {
+                                app.view_data.partners_view.merge_form = (
+                                    true,
+                                    MergeForm {
+                                        pairs,
+                                        ..Default::default()
+                                    },
+                                );
+                            }
+                            Err(e) => {
+                                app.notifications.error(&e);
+                            }
+                        }
+                    };
+                    if panel_button(ui, tr!("parse-addresses")) {
+                        match block_on(partners::parse_addresses(&app.db)) {
+                            Ok(unparsed) => {
+                                if !unparsed.is_empty() {
+                                    app.notifications.warning(tr!("addresses-unparsed", count = unparsed.len()));
+                                }
+                                app.view_data.partners_view.unparsed_addresses = (true, unparsed);
+                            }
+                            Err(e) => {
+                                app.notifications.error(&e);
+                            }
+                        }
+                    };
+                    if panel_button(ui, tr!("normalize-types")) {
+                        match block_on(partner_types::normalization_preview(&app.db)) {
+                            Ok(mappings) => {
+                                app.view_data.partners_view.normalization = (true, mappings);
+                            }
+                            Err(e) => {
+                                app.notifications.error(&e);
+                            }
+                        }
+                    };
                 });
 
+                let partner_form = FormView::<PartnersForm>::new(tr!("partner"))
+                    .field(Field::text(tr!("organization-name"), |form| &mut form.partner_name))
+                    .field(Field::choice(
+                        tr!("organization-type"),
+                        |form| &mut form.partner_type,
+                        partner_types_list.iter().map(|partner_type| partner_type.partner_type.clone()).collect(),
+                        String::clone,
+                    ))
+                    .field(Field::text(tr!("organization-director"), |form| &mut form.director))
+                    .field(Field::text(tr!("email"), |form| &mut form.email))
+                    .field(Field::text(tr!("contact-phone"), |form| &mut form.phone))
+                    .field(Field::text(tr!("postal-index"), |form| &mut form.address.postal_index))
+                    .field(Field::text(tr!("region"), |form| &mut form.address.region))
+                    .field(Field::text(tr!("city"), |form| &mut form.address.city))
+                    .field(Field::text(tr!("street"), |form| &mut form.address.street))
+                    .field(Field::text(tr!("building"), |form| &mut form.address.building))
+                    .field(Field::text(tr!("inn"), |form| &mut form.inn))
+                    .field(Field::number(tr!("rating"), |form| &mut form.rating))
+                    .validate(|form| form.address.validate().err().map(|e| i18n::error_message(&e)));
+
                 let mut change_view = false;
                 let form_opened = &mut app.view_data.partners_view.form.0;
                 let form = &mut app.view_data.partners_view.form.1;
-                egui::Window::new("Партнер")
+                egui::Window::new(tr!("partner"))
                     .open(form_opened)
                     .show(ctx, |ui| {
                         ui.vertical_centered(|ui| {
-                            // let mut form = PartnersForm::default();
-
-                            ui.label("Название организации");
-                            ui.text_edit_singleline(&mut form.partner_name);
-
-                            ui.label("Тип организации");
-                            ui.text_edit_singleline(&mut form.partner_type);
-
-                            ui.label("Директор организации");
-                            ui.text_edit_singleline(&mut form.director);
-
-                            ui.label("E-Mail");
-                            ui.text_edit_singleline(&mut form.email);
-
-                            ui.label("Контактный номер");
-                            ui.text_edit_singleline(&mut form.phone);
+                            let entered = partner_form.show_fields(ui, form);
+
+                            let possible_duplicates = &mut app.view_data.partners_view.duplicates;
+                            if !possible_duplicates.is_empty() {
+                                ui.separator();
+                                ui.label(RichText::new(tr!("possible-duplicates")).color(Color32::DARK_RED));
+                                possible_duplicates.iter().for_each(|duplicate| {
+                                    ui.label(tr!(
+                                        "possible-duplicate",
+                                        name = duplicate.partner.partner_name.as_str(),
+                                        kind = duplicate.partner.partner_type.as_str(),
+                                        inn = duplicate.partner.inn.as_str(),
+                                        reason = i18n::duplicate_reasons(&duplicate.reasons),
+                                    ));
+                                });
+                            }
+
+                            let problem = partner_form.problem(form);
+                            let submit = ui
+                                .add_enabled(problem.is_none(), egui::Button::new(RichText::new(tr!("submit")).size(20.0)))
+                                .clicked()
+                                || (entered && problem.is_none());
+                            let submit_anyway = !possible_duplicates.is_empty()
+                                && ui
+                                    .add_enabled(
+                                        problem.is_none(),
+                                        egui::Button::new(RichText::new(tr!("submit-anyway")).size(20.0)),
+                                    )
+                                    .clicked();
+                            if let Some(problem) = &problem {
+                                ui.label(RichText::new(problem).weak());
+                            }
+
+                            if submit || submit_anyway {
+                                let mut transaction = || -> demo_core::Result<bool> {
+                                    form.address.validate()?;
+                                    let partner = Partner::new(
+                                        form.partner_type.clone().unwrap_or_default(),
+                                        form.partner_name.clone(),
+                                        form.director.clone(),
+                                        form.email.clone(),
+                                        form.phone.clone(),
+                                        form.address.clone(),
+                                        form.inn.clone(),
+                                        form.rating,
+                                    );
 
-                            ui.label("Адресс");
-                            ui.text_edit_singleline(&mut form.legal_address);
+                                    if !submit_anyway {
+                                        *possible_duplicates = block_on(duplicates::find_duplicates(&app.db, &partner))?;
+                                        if !possible_duplicates.is_empty() {
+                                            return Ok(false);
+                                        }
+                                    }
 
-                            ui.label("ИНН");
-                            ui.text_edit_singleline(&mut form.inn);
+                                    block_on(partner.insert(&app.db))?;
 
-                            ui.label("Рейтинг");
-                            ui.add(DragValue::new(&mut form.rating));
+                                    Ok(true)
+                                };
 
-                            if ui.button(RichText::new("Внести").size(20.0)).clicked() {
-                                let partner = Partner::new(
-                                    form.partner_type.clone(),
-                                    form.partner_name.clone(),
-                                    form.director.clone(),
-                                    form.email.clone(),
-                                    form.phone.clone(),
-                                    form.legal_address.clone(),
-                                    form.inn.clone(),
-                                    form.rating,
-                                );
-                                match block_on(partner.create(&app.db)) {
-                                    Ok(_) => {
-                                        change_view = true;
+                                match transaction() {
+                                    Ok(created) => {
+                                        if created {
+                                            app.notifications.success(tr!("partner-created"));
+                                        }
+                                        change_view = created;
                                     }
                                     Err(e) => {
-                                        app.view_data.partners_view.error.0 = true;
-                                        app.view_data.partners_view.error.1 = e.to_string();
+                                        app.notifications.error(&e);
                                     }
                                 }
                             };
@@ -261,502 +815,1102 @@ pub fn show(app: &mut MyEguiApp, ctx: &egui::Context) {
 
                 if change_view {
                     *form_opened = false;
+                    app.view_data.partners_view.duplicates.clear();
                 }
 
-                let message = app.view_data.partners_view.error.1.clone();
-                egui::Window::new("Ошибка")
-                    .open(&mut app.view_data.partners_view.error.0)
+                let rating_fields = FormView::<RatingForm>::new(tr!("change-rating"))
+                    .field(Field::number(tr!("new-rating"), |form: &mut RatingForm| &mut form.rating).range(0.0..=i16::MAX as f64))
+                    .field(Field::multiline(tr!("rating-reason"), |form| &mut form.comment));
+
+                let mut rating_changed = false;
+                let rating_form_opened = &mut app.view_data.partners_view.rating_form.0;
+                let rating_form = &mut app.view_data.partners_view.rating_form.1;
+                egui::Window::new(tr!("change-rating"))
+                    .open(rating_form_opened)
                     .show(ctx, |ui| {
                         ui.vertical_centered(|ui| {
-                            ui.label(message);
+                            ui.label(RichText::new(
+                                rating_form.partner.clone().unwrap_or_default().partner_name,
+                            ).strong());
+
+                            let entered = rating_fields.show_fields(ui, rating_form);
+
+                            let can_submit = !rating_form.comment.trim().is_empty();
+                            let clicked = ui
+                                .add_enabled(can_submit, egui::Button::new(RichText::new(tr!("submit")).size(20.0)))
+                                .on_disabled_hover_text(tr!("rating-reason-required"))
+                                .clicked();
+                            if clicked || (entered && can_submit) {
+                                let mut partner = rating_form.partner.clone().unwrap_or_default();
+                                match block_on(partner.change_rating(
+                                    &app.db,
+                                    rating_form.rating,
+                                    rating_form.comment.clone(),
+                                )) {
+                                    Ok(_) => {
+                                        app.notifications.success(tr!("rating-changed"));
+                                        rating_changed = true;
+                                    }
+                                    Err(e) => {
+                                        app.notifications.error(&e);
+                                    }
+                                }
+                            };
                         })
                     });
 
-                egui::CentralPanel::default().show(ctx, |ui| {
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        partners_list.into_iter().for_each(|partner| {
-                            egui::Frame::default()
-                                .stroke(Stroke::new(1.0, Color32::GRAY))
-                                .inner_margin(0.4)
-                                .outer_margin(0.8)
-                                .show(ui, |ui| {
-                                    ui.set_min_size((ui.available_width() - 0.2, 0.0).into());
-
-                                    ui.collapsing(
-                                        RichText::new(
-                                            [&partner.partner_name, " | ", &partner.partner_type]
-                                                .concat(),
-                                        )
-                                        .strong()
-                                        .size(20.0),
-                                        |ui| {
-                                            if ui.button("Удалить").clicked() {
-                                                match block_on(partner.delete(&app.db)) {
-                                                    Ok(_res) => {}
-                                                    Err(e) => {
-                                                        app.view_data.partners_view.error.0 = true;
-                                                        app.view_data.partners_view.error.1 =
-                                                            e.to_string();
-                                                    }
-                                                }
-                                            }
-                                        },
-                                    );
-
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Адресс: ").size(14.0));
-                                        ui.label(partner.legal_address.clone());
-                                    });
+                if rating_changed {
+                    *rating_form_opened = false;
+                }
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Директор: ").size(14.0));
-                                        ui.label(partner.director.clone());
-                                    });
+                let partner_types_form = &mut app.view_data.partners_view.partner_types_form.1;
+                egui::Window::new(tr!("partner-types"))
+                    .open(&mut app.view_data.partners_view.partner_types_form.0)
+                    .show(ctx, |ui| {
+                        ui.vertical_centered(|ui| {
+                            partner_types_list.iter().for_each(|partner_type| {
+                                ui.horizontal(|ui| {
+                                    ui.label(RichText::new(&partner_type.partner_type).size(16.0));
+                                    if ui.button(tr!("delete")).clicked() {
+                                        if let Err(e) = block_on(partner_type.delete(&app.db)) {
+                                            app.notifications.error(&e);
+                                        }
+                                    }
+                                });
+                            });
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("E-Mail: ").size(14.0));
-                                        ui.label(partner.email.clone());
-                                    });
+                            ui.separator();
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Телефон: ").size(14.0));
-                                        ui.label(partner.phone.clone());
-                                    });
+                            ui.label(tr!("name"));
+                            ui.text_edit_singleline(&mut partner_types_form.partner_type);
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("ИНН: ").size(14.0));
-                                        ui.label(partner.inn.clone());
-                                    });
+                            if ui.button(RichText::new(tr!("submit")).size(20.0)).clicked() {
+                                let partner_type = PartnerType::new(partner_types_form.partner_type.trim().to_string());
+                                match block_on(partner_type.create(&app.db)) {
+                                    Ok(_) => {
+                                        partner_types_form.partner_type.clear();
+                                    }
+                                    Err(e) => {
+                                        app.notifications.error(&e);
+                                    }
+                                }
+                            };
+                        })
+                    });
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Рейтинг: ").size(14.0));
-                                        ui.label(partner.rating.to_string());
+                let mut normalized = false;
+                let normalization_opened = &mut app.view_data.partners_view.normalization.0;
+                let mappings = &mut app.view_data.partners_view.normalization.1;
+                egui::Window::new(tr!("normalize-types"))
+                    .open(normalization_opened)
+                    .show(ctx, |ui| {
+                        if mappings.is_empty() {
+                            ui.label(tr!("types-match"));
+                            return;
+                        }
+
+                        egui::Grid::new("normalization_grid").striped(true).show(ui, |ui| {
+                            ui.label(RichText::new(tr!("value")).strong());
+                            ui.label(RichText::new(tr!("partners-count")).strong());
+                            ui.label(RichText::new(tr!("replace-with")).strong());
+                            ui.end_row();
+
+                            mappings.iter_mut().for_each(|mapping| {
+                                ui.label(["\"", &mapping.from, "\""].concat());
+                                ui.label(mapping.partners.to_string());
+                                ComboBox::from_id_salt(["normalization_", &mapping.from].concat())
+                                    .selected_text(match &mapping.to {
+                                        Some(to) => to.partner_type.clone(),
+                                        None => tr!("keep"),
+                                    })
+                                    .show_ui(ui, |ui| {
+                                        ui.selectable_value(&mut mapping.to, None, tr!("keep"));
+                                        partner_types_list
+                                            .iter()
+                                            .filter(|partner_type| partner_type.partner_type != mapping.from)
+                                            .for_each(|partner_type| {
+                                                ui.selectable_value(
+                                                    &mut mapping.to,
+                                                    Some(partner_type.clone()),
+                                                    &partner_type.partner_type,
+                                                );
+                                            })
                                     });
-                                });
+                                ui.end_row();
+                            });
                         });
+
+                        if ui.button(RichText::new(tr!("apply")).size(20.0)).clicked() {
+                            match block_on(partner_types::normalize(&app.db, mappings)) {
+                                Ok(_) => {
+                                    app.notifications.success(tr!("types-normalized"));
+                                    normalized = true;
+                                }
+                                Err(e) => {
+                                    app.notifications.error(&e);
+                                }
+                            }
+                        };
                     });
-                });
-            } // PartnersView
-            Views::Sales => {
-                let sales = match block_on(sales::get_all(&app.db)) {
-                    Ok(sales) => sales,
-                    Err(e) => {
-                        app.view_data.sales_views.error.0 = true;
-                        app.view_data.sales_views.error.1 = e.to_string();
-                        vec![]
-                    }
-                };
 
-                let partners_list = match block_on(partners::get_all(&app.db)) {
-                    Ok(values) => values,
-                    Err(e) => {
-                        app.view_data.sales_views.error.0 = true;
-                        app.view_data.sales_views.error.1 = e.to_string();
-                        vec![]
-                    }
-                };
+                if normalized {
+                    *normalization_opened = false;
+                    mappings.clear();
+                }
 
-                let products_list = match block_on(products::get_all(&app.db)) {
-                    Ok(values) => values,
-                    Err(e) => {
-                        app.view_data.sales_views.error.0 = true;
-                        app.view_data.sales_views.error.1 = e.to_string();
-                        vec![]
-                    }
-                };
+                let mut merged = false;
+                let merge_form = &mut app.view_data.partners_view.merge_form.1;
+                egui::Window::new(tr!("merge-partners"))
+                    .open(&mut app.view_data.partners_view.merge_form.0)
+                    .show(ctx, |ui| {
+                        if merge_form.pairs.is_empty() {
+                            ui.label(tr!("no-duplicates"));
+                        }
+                        merge_form.pairs.clone().into_iter().for_each(|(first, second)| {
+                            ui.horizontal(|ui| {
+                                ui.label(tr!(
+                                    "duplicate-pair",
+                                    first = first.partner_name.as_str(),
+                                    second = second.partner.partner_name.as_str(),
+                                    reason = i18n::duplicate_reasons(&second.reasons),
+                                ));
+                                if ui.button(tr!("choose")).clicked() {
+                                    merge_form.survivor = Some(first);
+                                    merge_form.duplicate = Some(second.partner);
+                                    merge_form.from_duplicate.clear();
+                                }
+                            });
+                        });
 
-                ctx.send_viewport_cmd(egui::ViewportCommand::Title("Продажи".into()));
-                egui::SidePanel::right("sales_panel").show(ctx, |ui| {
-                    if ui.button(RichText::new("Добавить").size(20.0)).clicked() {
-                        app.view_data.sales_views.form.0 = true
-                    };
-                });
+                        ui.separator();
 
-                let mut change_view = false;
-                let form_opened = &mut app.view_data.sales_views.form.0;
-                let form = &mut app.view_data.sales_views.form.1;
-                egui::Window::new("Продажа")
-                    .open(form_opened)
-                    .show(ctx, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label("Продукт");
-                            ComboBox::from_id_salt("products_combo_box")
-                                .selected_text(
-                                    &form.product.clone().unwrap_or_default().product_name,
-                                )
+                        egui::Grid::new("merge_grid").striped(true).show(ui, |ui| {
+                            ui.label("");
+                            ui.label(RichText::new(tr!("survivor")).strong());
+                            ui.label(RichText::new(tr!("duplicate")).strong());
+                            ui.end_row();
+
+                            ui.label("");
+                            ComboBox::from_id_salt("merge_survivor_combo_box")
+                                .selected_text(merge_form.survivor.clone().unwrap_or_default().partner_name)
                                 .show_ui(ui, |ui| {
-                                    products_list.into_iter().for_each(|product| {
-                                        let name =
-                                            [&product.product_name, " | ", &product.product_type]
-                                                .concat();
-                                        ui.selectable_value(&mut form.product, Some(product), name);
+                                    partners_list.iter().for_each(|partner| {
+                                        ui.selectable_value(&mut merge_form.survivor, Some(partner.clone()), &partner.partner_name);
                                     })
                                 });
-
-                            ui.label("Количество");
-                            ui.add(DragValue::new(&mut form.quantity));
-
-                            ui.label("Дата прдажи");
-                            ui.add(DatePickerButton::new(&mut form.sale_date));
-
-                            ui.label("Партнер");
-                            ComboBox::from_id_salt("partners_combo_box")
-                                .selected_text(
-                                    &form.partner.clone().unwrap_or_default().partner_name,
-                                )
+                            ComboBox::from_id_salt("merge_duplicate_combo_box")
+                                .selected_text(merge_form.duplicate.clone().unwrap_or_default().partner_name)
                                 .show_ui(ui, |ui| {
-                                    partners_list.into_iter().for_each(|partner| {
-                                        let name =
-                                            [&partner.partner_name, " | ", &partner.partner_type]
-                                                .concat();
-                                        ui.selectable_value(&mut form.partner, Some(partner), name);
+                                    partners_list.iter().for_each(|partner| {
+                                        ui.selectable_value(&mut merge_form.duplicate, Some(partner.clone()), &partner.partner_name);
                                     })
                                 });
+                            ui.end_row();
 
-                            if ui.button(RichText::new("Внести").size(20.0)).clicked() {
-                                let transaction = || -> crate::Result<()> {
-                                    let partner = Sale::new(
-                                        form.product.clone().unwrap_or_default().id,
-                                        form.quantity.clone(),
-                                        Date::from_ordinal_date(
-                                            form.sale_date.year_ce().1 as i32,
-                                            form.sale_date.ordinal0().try_into().unwrap(),
-                                        )
-                                        .map_err(|e| crate::Error::Any(e.to_string()))?,
-                                        form.partner.clone().unwrap_or_default().id,
-                                    );
+                            let (Some(survivor), Some(duplicate)) = (&merge_form.survivor, &merge_form.duplicate) else {
+                                return;
+                            };
 
-                                    block_on(partner.create(&app.db))?;
+                            PartnerField::ALL.iter().for_each(|field| {
+                                let from_duplicate = merge_form.from_duplicate.contains(field);
+                                ui.label(i18n::partner_field(*field));
+                                if ui.radio(!from_duplicate, field.value(survivor)).clicked() {
+                                    merge_form.from_duplicate.retain(|f| f != field);
+                                }
+                                if ui.radio(from_duplicate, field.value(duplicate)).clicked() && !from_duplicate {
+                                    merge_form.from_duplicate.push(*field);
+                                }
+                                ui.end_row();
+                            });
+                        });
 
-                                    Ok(())
-                                };
+                        ui.label(tr!("merge-note"));
+
+                        let ready = merge_form.survivor.is_some() && merge_form.duplicate.is_some();
+                        if ui.add_enabled(ready, egui::Button::new(RichText::new(tr!("merge")).size(20.0))).clicked() {
+                            let mut transaction = || -> demo_core::Result<()> {
+                                block_on(duplicates::merge(
+                                    &app.db,
+                                    &merge_form.survivor.clone().unwrap_or_default(),
+                                    &merge_form.duplicate.clone().unwrap_or_default(),
+                                    &merge_form.from_duplicate,
+                                ))?;
+                                merge_form.pairs = block_on(duplicates::find_all_duplicates(&app.db))?;
+                                Ok(())
+                            };
 
-                                match transaction() {
-                                    Ok(_) => {
-                                        change_view = true;
-                                    }
-                                    Err(e) => {
-                                        app.view_data.sales_views.error.0 = true;
-                                        app.view_data.sales_views.error.1 = e.to_string();
-                                    }
+                            match transaction() {
+                                Ok(_) => {
+                                    app.notifications.success(tr!("partners-merged"));
+                                    merged = true;
                                 }
-                            };
-                        })
+                                Err(e) => {
+                                    app.notifications.error(&e);
+                                }
+                            }
+                        };
                     });
 
-                if change_view {
-                    *form_opened = false;
+                if merged {
+                    let merge_form = &mut app.view_data.partners_view.merge_form.1;
+                    merge_form.survivor = None;
+                    merge_form.duplicate = None;
+                    merge_form.from_duplicate.clear();
                 }
 
-                let message = app.view_data.partners_view.error.1.clone();
-                egui::Window::new("Ошибка")
-                    .open(&mut app.view_data.partners_view.error.0)
+                let unparsed_addresses = &app.view_data.partners_view.unparsed_addresses.1;
+                egui::Window::new(tr!("address-parsing"))
+                    .open(&mut app.view_data.partners_view.unparsed_addresses.0)
                     .show(ctx, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label(message);
-                        })
+                        if unparsed_addresses.is_empty() {
+                            ui.label(tr!("addresses-parsed"));
+                            return;
+                        }
+
+                        ui.label(tr!("addresses-unparsed", count = unparsed_addresses.len()));
+                        egui::ScrollArea::vertical().show(ui, |ui| {
+                            unparsed_addresses.iter().for_each(|unparsed| {
+                                ui.separator();
+                                ui.label(RichText::new(&unparsed.partner.partner_name).strong());
+                                ui.label(&unparsed.partner.legal_address);
+                                ui.label(RichText::new(i18n::error_message(&unparsed.reason)).color(Color32::RED));
+                            });
+                        });
                     });
 
+                let regions = block_on(partners::get_regions(&app.db));
+                let cities = block_on(partners::get_cities(
+                    &app.db,
+                    app.view_data.partners_view.filter.region.clone(),
+                ));
+                let (regions, cities) = match (regions, cities) {
+                    (Ok(regions), Ok(cities)) => (regions, cities),
+                    (Err(e), _) | (_, Err(e)) => {
+                        app.notifications.error(&e);
+                        (vec![], vec![])
+                    }
+                };
+
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    egui::ScrollArea::vertical().show(ui, |ui| {
-                        sales.into_iter().for_each(|sale| {
-                            egui::Frame::default()
-                                .stroke(Stroke::new(1.0, Color32::GRAY))
-                                .inner_margin(0.4)
-                                .outer_margin(0.8)
-                                .show(ui, |ui| {
-                                    ui.set_min_size((ui.available_width() - 0.2, 0.0).into());
-
-                                    let name = match block_on(products::get(&app.db, sale.product_id.clone())) {
-                                        Ok(product) => product.product_name,
-                                        Err(_) => "Ошибка".to_string(),
-                                    };
-                                    
-                                    ui.collapsing(
-                                        RichText::new(name).strong().size(20.0),
-                                        |ui| {
-                                            if ui.button("Удалить").clicked() {
-                                                match block_on(sale.delete(&app.db)) {
-                                                    Ok(_res) => {}
-                                                    Err(e) => {
-                                                        app.view_data.partners_view.error.0 = true;
-                                                        app.view_data.partners_view.error.1 =
-                                                            e.to_string();
-                                                    }
-                                                }
-                                            }
-                                        },
-                                    );
+                    let filter = &mut app.view_data.partners_view.filter;
+                    ui.horizontal(|ui| {
+                        ui.label(tr!("region"));
+                        let previous_region = filter.region.clone();
+                        ComboBox::from_id_salt("region_filter")
+                            .selected_text(filter.region.clone().unwrap_or(tr!("all")))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut filter.region, None, tr!("all"));
+                                regions.into_iter().for_each(|region| {
+                                    ui.selectable_value(&mut filter.region, Some(region.clone()), region);
+                                })
+                            });
+                        // Cities of the previously chosen region make no sense for the new one
+                        if filter.region != previous_region {
+                            filter.city = None;
+                        }
+
+                        ui.label(tr!("city"));
+                        ComboBox::from_id_salt("city_filter")
+                            .selected_text(filter.city.clone().unwrap_or(tr!("all")))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut filter.city, None, tr!("all"));
+                                cities.into_iter().for_each(|city| {
+                                    ui.selectable_value(&mut filter.city, Some(city.clone()), city);
+                                })
+                            });
+                    });
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Количество: ").size(14.0));
-                                        ui.label(sale.quantity.to_string());
-                                    });
+                    let pager = &app.view_data.partners_view.pager;
+                    ui.label(tr!("shown-of", shown = pager.items().len(), total = pager.total()));
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Дата: ").size(14.0));
-                                        ui.label(sale.sale_date.to_string());
-                                    });
+                    let rating_form = &mut app.view_data.partners_view.rating_form;
+                    let notifications = &mut app.notifications;
+                    let reveal = app.view_data.reveal.take();
+                    let mut load_more = false;
+                    let deleted = ListView::new("partners_scroll", |partner: &Partner| {
+                        [&partner.partner_name, " | ", &partner.partner_type].concat()
+                    })
+                    .deletable()
+                    .reveal(|partner| reveal.as_ref() == Some(&partner.id))
+                    .paged(pager.has_more(), || load_more = true)
+                    .actions(|ui, partner| {
+                        if ui.button(tr!("change-rating")).clicked() {
+                            *rating_form = (
+                                true,
+                                RatingForm {
+                                    partner: Some(partner.clone()),
+                                    rating: partner.rating,
+                                    comment: "".to_string(),
+                                },
+                            );
+                        }
+
+                        match block_on(partner_rating_history::get_by_partner(&app.db, partner.id.clone())) {
+                            Ok(history) => rating_chart(ui, partner, &history),
+                            Err(e) => notifications.error(&e),
+                        }
+                    })
+                    .field(tr!("address"), |partner| partner.legal_address.clone())
+                    .field(tr!("director"), |partner| partner.director.clone())
+                    .field(tr!("email"), |partner| partner.email.clone())
+                    .field(tr!("phone"), |partner| partner.phone.clone())
+                    .field(tr!("inn"), |partner| partner.inn.clone())
+                    .field(tr!("rating"), |partner| partner.rating.to_string())
+                    .show(ui, pager.items().to_vec());
+
+                    if load_more {
+                        let pager = &mut app.view_data.partners_view.pager;
+                        if let Err(e) = block_on(pager.load_more(&app.db)) {
+                            app.notifications.error(&e);
+                        }
+                    }
 
-                                    ui.horizontal(|ui| {
-                                        let partner: String =
-                                            match block_on(partners::get(&app.db, sale.partner_id))
-                                            {
-                                                Ok(partner) => [
-                                                    &partner.partner_name,
-                                                    " | ",
-                                                    &partner.partner_type,
-                                                ]
-                                                .concat()
-                                                .to_string(),
-                                                Err(_) => "Ошибка".into(),
-                                            };
-                                        ui.label(RichText::new("Партнер: ").size(14.0));
-                                        ui.label(partner);
-                                    });
-                                });
-                        });
-                    });
+                    if let Some(partner) = deleted {
+                        if let Err(e) = block_on(partner.delete(&app.db)) {
+                            app.notifications.error(&e);
+                        }
+                    }
                 });
-            }, // SalesView
-            Views::Products => {
-                let products_list = match block_on(products::get_all(&app.db)) {
-                    Ok(values) => values,
-                    Err(e) => {
-                        app.view_data.products_view.error.0 = true;
-                        app.view_data.products_view.error.1 = e.to_string();
-                        vec![]
+            } // PartnersView
+            Views::Sales => {
+                let notifications = &mut app.notifications;
+                let sales_view = &mut app.view_data.sales_views;
+                refresh(&app.db, &mut sales_view.pager, sales_view.filter.filters(), &app.view_data.reveal, |details| &details.sale.id, notifications);
+                sales_view.choices.refresh(&app.db, notifications);
+                let partners_list = &app.view_data.sales_views.choices.partners;
+                let products_list = &app.view_data.sales_views.choices.products;
+                let product_types = &app.view_data.sales_views.choices.product_types;
+
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("sales")));
+                egui::SidePanel::right("sales_panel").show(ctx, |ui| {
+                    if panel_button(ui, tr!("add")) {
+                        app.view_data.sales_views.form.0 = true
+                    };
+                });
+
+                // The remembered partner and product may have been changed or deleted since
+                let form = &mut app.view_data.sales_views.form.1;
+                form.partner = form.partner.take().and_then(|partner| partners_list.iter().find(|p| **p == partner).cloned());
+                form.product = form.product.take().and_then(|product| products_list.iter().find(|p| **p == product).cloned());
+
+                let mut submitted = None;
+                let (open, form) = &mut app.view_data.sales_views.form;
+                FormView::<SalesForm>::new(tr!("sale"))
+                    .field(Field::choice(tr!("product"), |form| &mut form.product, products_list.clone(), |product| {
+                        [&product.product_name, " | ", &product.product_type].concat()
+                    }))
+                    .field(Field::number(tr!("quantity"), |form| &mut form.quantity))
+                    .field(Field::date(tr!("sale-date"), |form| &mut form.sale_date))
+                    .field(Field::choice(tr!("partner"), |form| &mut form.partner, partners_list.clone(), |partner| {
+                        [&partner.partner_name, " | ", &partner.partner_type].concat()
+                    }))
+                    .validate(|form| form.sale().err().map(|e| i18n::error_message(&e)))
+                    .show(ctx, open, form, &mut app.notifications, |form| {
+                        let sale = form.sale()?;
+                        block_on(sale.insert(&app.db))?;
+                        submitted = Some(form.next());
+
+                        Ok(())
+                    });
+                if let Some(next) = submitted {
+                    *form = next;
+                }
+
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let filter = &mut app.view_data.sales_views.filter;
+                    let preset_name = &mut app.view_data.sales_views.preset_name;
+                    egui::CollapsingHeader::new(tr!("filters")).show(ui, |ui| {
+                        sales_filter(ui, filter, partners_list, products_list, product_types);
+                        ui.separator();
+                        sales_presets(ui, &mut app.sales_presets, preset_name, filter);
+                    });
+
+                    let pager = &app.view_data.sales_views.pager;
+                    ui.label(tr!("shown-of", shown = pager.items().len(), total = pager.total()));
+
+                    let notifications = &mut app.notifications;
+                    let reveal = app.view_data.reveal.take();
+                    let mut load_more = false;
+                    let deleted = ListView::new("sales_scroll", |details: &SaleDetails| details.product_name.clone())
+                    .deletable()
+                    .reveal(|details| reveal.as_ref() == Some(&details.sale.id))
+                    .paged(pager.has_more(), || load_more = true)
+                    .actions(|ui, details| {
+                        ui.menu_button(tr!("print"), |ui| {
+                            for document_type in [DocumentType::Invoice, DocumentType::DeliveryNote] {
+                                if ui.button(i18n::document_title(document_type)).clicked() {
+                                    let print = || -> demo_core::Result<PathBuf> {
+                                        let path = block_on(printing::save(
+                                            &app.db,
+                                            &details.sale,
+                                            document_type,
+                                            Path::new(DOCUMENTS_DIR),
+                                        ))?;
+                                        open::that_detached(&path)
+                                            .map_err(|e| demo_core::Error::Any(e.to_string()))?;
+                                        Ok(path)
+                                    };
+                                    match print() {
+                                        Ok(path) => notifications.info(tr!("document-saved", path = path.display().to_string())),
+                                        Err(e) => notifications.error(&e),
+                                    }
+                                    ui.close_menu();
+                                }
+                            }
+                        });
+                    })
+                    .field(tr!("quantity"), |details| details.sale.quantity.to_string())
+                    .field(tr!("date"), |details| format_date(details.sale.sale_date))
+                    .field(tr!("partner"), |details| [&details.partner_name, " | ", &details.partner_type].concat())
+                    .show(ui, pager.items().to_vec());
+
+                    if load_more {
+                        let pager = &mut app.view_data.sales_views.pager;
+                        if let Err(e) = block_on(pager.load_more(&app.db)) {
+                            app.notifications.error(&e);
+                        }
                     }
-                };
 
-                let product_types = match block_on(product_types::get_all(&app.db)) {
-                    Ok(values) => values,
-                    Err(e) => {
-                        app.view_data.products_view.error.0 = true;
-                        app.view_data.products_view.error.1 = e.to_string();
-                        vec![]
+                    if let Some(details) = deleted {
+                        if let Err(e) = block_on(details.sale.delete(&app.db)) {
+                            app.notifications.error(&e);
+                        }
                     }
-                };
+                });
+            }, // SalesView
+            Views::Products => {
+                let notifications = &mut app.notifications;
+                refresh(&app.db, &mut app.view_data.products_view.pager, vec![], &app.view_data.reveal, |product| &product.id, notifications);
+                let product_types = or_report(block_on(ProductType::list(&app.db, &[], None)), notifications);
 
-                ctx.send_viewport_cmd(egui::ViewportCommand::Title("Продукты".into()));
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("products")));
                 egui::SidePanel::right("sales_panel").show(ctx, |ui| {
-                    if ui.button(RichText::new("Добавить").size(20.0)).clicked() {
+                    if panel_button(ui, tr!("add")) {
                         app.view_data.products_view.products_form.0 = true
                     };
-                    if ui.button(RichText::new("Новый тип").size(20.0)).clicked() {
+                    if panel_button(ui, tr!("new-type")) {
                         app.view_data.products_view.product_types_form.0 = true
                     };
                 });
 
-                let mut change_view = false;
+                let products_view = &mut app.view_data.products_view;
+                FormView::<ProductsForm>::new(tr!("product"))
+                    .field(Field::choice(tr!("type"), |form| &mut form.product_type, product_types.clone(), |product_type| {
+                        product_type.product_type.clone()
+                    }))
+                    .field(Field::text(tr!("name"), |form| &mut form.product_name))
+                    .field(Field::text(tr!("article-number"), |form| &mut form.article_number))
+                    .field(Field::number(tr!("minimum-cost"), |form| &mut form.minimum_cost))
+                    .validate(|form| form.product().err().map(|e| i18n::error_message(&e)))
+                    .show(ctx, &mut products_view.products_form.0, &mut products_view.products_form.1, &mut app.notifications, |form| {
+                        let product = form.product()?;
+
+                        block_on(product.insert(&app.db))?;
+
+                        Ok(())
+                    });
 
-                let product_form_opened = &mut app.view_data.products_view.products_form.0;
-                let product_form = &mut app.view_data.products_view.products_form.1;
+                FormView::<ProductTypesForm>::new(tr!("product-type"))
+                    .field(Field::text(tr!("name"), |form| &mut form.product_type))
+                    .field(Field::number(tr!("coefficient"), |form| &mut form.coefficient))
+                    .show(ctx, &mut products_view.product_types_form.0, &mut products_view.product_types_form.1, &mut app.notifications, |form| {
+                        let product_type = ProductType::new(form.product_type.clone(), form.coefficient);
 
+                        block_on(product_type.insert(&app.db))?;
 
-                let product_type_opened = &mut app.view_data.products_view.product_types_form.0;
-                let product_type = &mut app.view_data.products_view.product_types_form.1;
-                egui::Window::new("Продукт")
-                    .open(product_form_opened)
-                    .show(ctx, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label("Тип");
-                            ComboBox::from_id_salt("products_combo_box")
-                                .selected_text(
-                                    &product_form.product_type.clone().unwrap_or_default().product_type,
-                                )
-                                .show_ui(ui, |ui| {
-                                    product_types.clone().into_iter().for_each(|product| {
-                                        let name = product.product_type.clone();
-                                        ui.selectable_value(&mut product_form.product_type, Some(product), name);
-                                    })
-                                });
+                        Ok(())
+                    });
 
-                            ui.label("Название");
-                            ui.text_edit_singleline(&mut product_form.product_name);
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let pager = &app.view_data.products_view.pager;
+                    ui.label(tr!("shown-of", shown = pager.items().len(), total = pager.total()));
+
+                    let notifications = &mut app.notifications;
+                    let reveal = app.view_data.reveal.take();
+                    let mut load_more = false;
+                    let deleted = ListView::new("products_scroll", |product: &Product| product.product_name.clone())
+                        .deletable()
+                        .reveal(|product| reveal.as_ref() == Some(&product.id))
+                        .paged(pager.has_more(), || load_more = true)
+                        .field(tr!("type"), |product| product.product_type.clone())
+                        .field(tr!("article-number"), |product| product.article_number.clone())
+                        .field(tr!("minimum-cost"), |product| format_number(product.minimum_cost as f64, 0))
+                        .max_height(ui.available_height() / 2.0)
+                        .show(ui, pager.items().to_vec());
+                    if load_more {
+                        if let Err(e) = block_on(app.view_data.products_view.pager.load_more(&app.db)) {
+                            notifications.error(&e);
+                        }
+                    }
+                    if let Some(product) = deleted {
+                        if let Err(e) = block_on(product.delete(&app.db)) {
+                            notifications.error(&e);
+                        }
+                    }
 
-                            ui.label("Артикул");
-                            ui.text_edit_singleline(&mut product_form.article_number);
+                    ui.separator();
 
-                            ui.label("Минимальная цена");
-                            ui.add(DragValue::new(&mut product_form.minimum_cost));
+                    let deleted = ListView::new("types_scroll", |product_type: &ProductType| product_type.product_type.clone())
+                        .deletable()
+                        .field(tr!("coefficient"), |product_type| format_number(product_type.coefficient, 2))
+                        .show(ui, product_types);
+                    if let Some(product_type) = deleted {
+                        if let Err(e) = block_on(product_type.delete(&app.db)) {
+                            notifications.error(&e);
+                        }
+                    }
+                });
+            } // ProductsView
+            Views::Materials => {
+                let lists = &mut app.view_data.materials_view.lists;
+                lists.refresh(&app.db, &mut app.notifications);
+                let materials_list = lists.materials.clone();
+                let suppliers_list = lists.suppliers.clone();
+                let products_list = lists.products.clone();
+                let product_materials_list = lists.product_materials.clone();
+                let product_costs = lists.costs.clone();
+
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("materials")));
+                egui::SidePanel::right("materials_panel").show(ctx, |ui| {
+                    if panel_button(ui, tr!("add")) {
+                        app.view_data.materials_view.materials_form.0 = true
+                    };
+                    if panel_button(ui, tr!("new-supplier")) {
+                        app.view_data.materials_view.suppliers_form.0 = true
+                    };
+                    if panel_button(ui, tr!("product-materials")) {
+                        app.view_data.materials_view.product_materials_form.0 = true
+                    };
+                });
 
-                            if ui.button(RichText::new("Внести").size(20.0)).clicked() {
-                                let transaction = || -> crate::Result<()> {
-                                    let product = Product::new(
-                                        product_form.product_type.clone().unwrap_or_default().product_type,
-                                        product_form.product_name.clone(),
-                                        product_form.article_number.clone(),
-                                        product_form.minimum_cost.clone()
-                                    );
+                let materials_view = &mut app.view_data.materials_view;
+                FormView::<MaterialsForm>::new(tr!("material"))
+                    .field(Field::text(tr!("name"), |form| &mut form.material_name))
+                    .field(Field::text(tr!("unit"), |form| &mut form.unit))
+                    .field(Field::number(tr!("unit-price"), |form: &mut MaterialsForm| &mut form.price).speed(0.1))
+                    .field(Field::number(tr!("defect-percentage"), |form: &mut MaterialsForm| &mut form.defect_percentage).speed(0.1).range(0.0..=100.0))
+                    .field(Field::choice(tr!("supplier"), |form| &mut form.supplier, suppliers_list.clone(), |supplier| {
+                        supplier.supplier_name.clone()
+                    }))
+                    .validate(|form| form.material().err().map(|e| i18n::error_message(&e)))
+                    .show(ctx, &mut materials_view.materials_form.0, &mut materials_view.materials_form.1, &mut app.notifications, |form| {
+                        let material = form.material()?;
+
+                        block_on(material.create(&app.db))?;
+
+                        Ok(())
+                    });
 
-                                    block_on(product.create(&app.db))?;
+                FormView::<SuppliersForm>::new(tr!("supplier"))
+                    .field(Field::text(tr!("organization-name"), |form| &mut form.supplier_name))
+                    .field(Field::text(tr!("organization-type"), |form| &mut form.supplier_type))
+                    .field(Field::text(tr!("inn"), |form| &mut form.inn))
+                    .show(ctx, &mut materials_view.suppliers_form.0, &mut materials_view.suppliers_form.1, &mut app.notifications, |form| {
+                        let supplier = Supplier::new(
+                            form.supplier_type.clone(),
+                            form.supplier_name.clone(),
+                            form.inn.clone(),
+                        );
 
-                                    Ok(())
-                                };
+                        block_on(supplier.create(&app.db))?;
 
-                                match transaction() {
-                                    Ok(_) => {
-                                        change_view = true;
-                                    }
-                                    Err(e) => {
-                                        app.view_data.products_view.error.0 = true;
-                                        app.view_data.products_view.error.1 = e.to_string();
-                                    }
-                                }
-                            };
-                        })
+                        Ok(())
                     });
 
+                FormView::<ProductMaterialsForm>::new(tr!("product-materials"))
+                    .field(Field::choice(tr!("product"), |form| &mut form.product, products_list.clone(), |product| {
+                        product.product_name.clone()
+                    }))
+                    .field(Field::choice(tr!("material"), |form| &mut form.material, materials_list.clone(), |material| {
+                        material.material_name.clone()
+                    }))
+                    .field(Field::number(tr!("quantity-per-unit"), |form: &mut ProductMaterialsForm| &mut form.quantity).speed(0.1))
+                    .validate(|form| form.product_material().err().map(|e| i18n::error_message(&e)))
+                    .show(ctx, &mut materials_view.product_materials_form.0, &mut materials_view.product_materials_form.1, &mut app.notifications, |form| {
+                        let product_material = form.product_material()?;
+
+                        block_on(product_material.create(&app.db))?;
+
+                        Ok(())
+                    });
 
-                egui::Window::new("Тип продукта")
-                    .open(product_type_opened)
-                    .show(ctx, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label("Тип");
-
-                            ui.label("Название");
-                            ui.text_edit_singleline(&mut product_type.product_type);
-
-                            ui.label("Коэффицент");
-                            ui.add(DragValue::new(&mut product_type.coefficient));
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let notifications = &mut app.notifications;
+
+                    ui.heading(tr!("production-cost"));
+                    ListView::new("costs_scroll", |cost: &ProductCost| cost.product_name.clone())
+                        .field(tr!("material-cost"), |cost| format_number(cost.material_cost, 2))
+                        .field(tr!("minimum-cost"), |cost| format_number(cost.minimum_cost as f64, 0))
+                        .warning(|cost| {
+                            cost.is_below_material_cost()
+                                .then(|| tr!("below-material-cost"))
+                        })
+                        .max_height(ui.available_height() / 3.0)
+                        .show(ui, product_costs);
 
-                            if ui.button(RichText::new("Внести").size(20.0)).clicked() {
-                                let transaction = || -> crate::Result<()> {
-                                    let product_type = ProductType::new(
-                                        product_type.product_type.clone(),
-                                        product_type.coefficient.clone()
-                                    );
+                    ui.separator();
 
-                                    block_on(product_type.create(&app.db))?;
+                    ui.heading(tr!("product-materials"));
+                    let product_name = |id: &String| {
+                        products_list
+                            .iter()
+                            .find(|product| product.id == *id)
+                            .map(|product| product.product_name.clone())
+                            .unwrap_or(tr!("error"))
+                    };
+                    let material = |id: &String| materials_list.iter().find(|material| material.id == *id);
+                    let deleted = ListView::new("product_materials_scroll", |entry: &ProductMaterial| {
+                        [
+                            product_name(&entry.product_id),
+                            material(&entry.material_id).map(|material| material.material_name.clone()).unwrap_or(tr!("error")),
+                        ]
+                        .join(" | ")
+                    })
+                    .deletable()
+                    .field(tr!("quantity-per-unit"), |entry| {
+                        let unit = material(&entry.material_id).map(|material| material.unit.clone()).unwrap_or_default();
+                        format!("{} {}", format_number(entry.quantity, 2), unit)
+                    })
+                    .max_height(ui.available_height() / 3.0)
+                    .show(ui, product_materials_list);
+                    if let Some(entry) = deleted {
+                        if let Err(e) = block_on(entry.delete(&app.db)) {
+                            notifications.error(&e);
+                        }
+                    }
 
-                                    Ok(())
-                                };
+                    ui.separator();
 
-                                match transaction() {
-                                    Ok(_) => {
-                                        change_view = true;
-                                    }
-                                    Err(e) => {
-                                        app.view_data.products_view.error.0 = true;
-                                        app.view_data.products_view.error.1 = e.to_string();
-                                    }
-                                }
-                            };
+                    ui.heading(tr!("materials"));
+                    let reveal = app.view_data.reveal.take();
+                    let deleted = ListView::new("materials_scroll", |material: &Material| material.material_name.clone())
+                        .deletable()
+                        .reveal(|material| reveal.as_ref() == Some(&material.id))
+                        .field(tr!("price"), |material| format!("{} / {}", format_number(material.price, 2), material.unit))
+                        .field(tr!("defect"), |material| format!("{}%", format_number(material.defect_percentage, 2)))
+                        .field(tr!("effective-price"), |material| format_number(material.effective_price(), 2))
+                        .field(tr!("supplier"), |material| {
+                            suppliers_list
+                                .iter()
+                                .find(|supplier| supplier.id == material.supplier_id)
+                                .map(|supplier| supplier.supplier_name.clone())
+                                .unwrap_or(tr!("error"))
                         })
-                    });
-
-
-                if change_view {
-                    *product_form_opened = false;
-                }
+                        .max_height(ui.available_height() / 2.0)
+                        .show(ui, materials_list);
+                    if let Some(material) = deleted {
+                        if let Err(e) = block_on(material.delete(&app.db)) {
+                            notifications.error(&e);
+                        }
+                    }
 
-                let message = app.view_data.products_view.error.1.clone();
-                egui::Window::new("Ошибка")
-                    .open(&mut app.view_data.products_view.error.0)
-                    .show(ctx, |ui| {
-                        ui.vertical_centered(|ui| {
-                            ui.label(message);
-                        })
-                    });
+                    ui.separator();
 
+                    ui.heading(tr!("suppliers"));
+                    let deleted = ListView::new("suppliers_scroll", |supplier: &Supplier| {
+                        [&supplier.supplier_name, " | ", &supplier.supplier_type].concat()
+                    })
+                    .deletable()
+                    .field(tr!("inn"), |supplier| supplier.inn.clone())
+                    .show(ui, suppliers_list.clone());
+                    if let Some(supplier) = deleted {
+                        if let Err(e) = block_on(supplier.delete(&app.db)) {
+                            notifications.error(&e);
+                        }
+                    }
+                });
+            } // MaterialsView
+            Views::Settings => {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Title(tr!("settings")));
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    egui::ScrollArea::vertical()
-                        .id_salt("products_scroll")
-                        .max_height(ui.available_height() / 2.0)
-                        .show(ui, |ui| {
-                        products_list.into_iter().for_each(|product| {
-                            egui::Frame::default()
-                                .stroke(Stroke::new(1.0, Color32::GRAY))
-                                .inner_margin(0.4)
-                                .outer_margin(0.8)
-                                .show(ui, |ui| {
-                                    ui.set_min_size((ui.available_width() - 0.2, 0.0).into());
-
-                                    ui.collapsing(
-                                        RichText::new(product.product_name.clone()).strong().size(20.0),
-                                        |ui| {
-                                            if ui.button("Удалить").clicked() {
-                                                match block_on(product.delete(&app.db)) {
-                                                    Ok(_res) => {}
-                                                    Err(e) => {
-                                                        app.view_data.partners_view.error.0 = true;
-                                                        app.view_data.partners_view.error.1 =
-                                                            e.to_string();
-                                                    }
-                                                }
-                                            }
-                                        },
-                                    );
+                    let settings = &mut app.settings;
+                    let previous = settings.clone();
+
+                    ui.heading(tr!("settings"));
+                    egui::Grid::new("settings_grid").num_columns(2).spacing([24.0, 12.0]).show(ui, |ui| {
+                        ui.label(tr!("theme"));
+                        ui.horizontal(|ui| {
+                            ui.radio_value(&mut settings.theme, ThemePreference::Light, tr!("theme-light"));
+                            ui.radio_value(&mut settings.theme, ThemePreference::Dark, tr!("theme-dark"));
+                            ui.radio_value(&mut settings.theme, ThemePreference::System, tr!("theme-system"));
+                        });
+                        ui.end_row();
+
+                        ui.label(tr!("ui-scale"));
+                        // Zooming while dragging would move the slider away from the pointer
+                        let scale = ui.add(
+                            egui::Slider::new(&mut settings.scale, settings::SCALES)
+                                .step_by(0.05)
+                                .custom_formatter(|value, _| format!("{:.0}%", value * 100.0)),
+                        );
+                        ui.end_row();
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Тип: ").size(14.0));
-                                        ui.label(product.product_type.to_string());
-                                    });
+                        ui.label(tr!("font-size"));
+                        ui.add(egui::Slider::new(&mut settings.font_size, settings::FONT_SIZES).step_by(0.5));
+                        ui.end_row();
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Артикул: ").size(14.0));
-                                        ui.label(product.article_number.to_string());
-                                    });
+                        ui.label(tr!("language"));
+                        language_switcher(ui, "settings_language");
+                        ui.end_row();
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Минимальная цена: ").size(14.0));
-                                        ui.label(product.minimum_cost.to_string());
-                                    });
-                                });
-                        });
+                        if (*settings != previous && !scale.dragged()) || scale.drag_stopped() {
+                            settings.apply(ctx);
+                        }
                     });
 
-                    ui.separator();
-
-                    egui::ScrollArea::vertical().id_salt("types_scroll").show(ui, |ui| {
-                        product_types.into_iter().for_each(|product_type| {
-                            egui::Frame::default()
-                                .stroke(Stroke::new(1.0, Color32::GRAY))
-                                .inner_margin(0.4)
-                                .outer_margin(0.8)
-                                .show(ui, |ui| {
-                                    ui.set_min_size((ui.available_width() - 0.2, 0.0).into());
-
-                                    ui.collapsing(
-                                        RichText::new(product_type.product_type.clone()).strong().size(20.0),
-                                        |ui| {
-                                            if ui.button("Удалить").clicked() {
-                                                match block_on(product_type.delete(&app.db)) {
-                                                    Ok(_res) => {}
-                                                    Err(e) => {
-                                                        app.view_data.products_view.error.0 = true;
-                                                        app.view_data.products_view.error.1 = e.to_string();
-                                                    }
-                                                }
-                                            }
-                                        },
-                                    );
+                    if ui.button(tr!("reset-settings")).clicked() {
+                        *settings = Settings::default();
+                        settings.apply(ctx);
+                    }
 
-                                    ui.horizontal(|ui| {
-                                        ui.label(RichText::new("Коэффицент: ").size(14.0));
-                                        ui.label(product_type.coefficient.to_string());
-                                    });
-                                });
-                        });
-                    });
+                    ui.separator();
+                    ui.label(tr!("saved-session-hint"));
+                    if ui.button(tr!("discard-session")).clicked() {
+                        app.discard_session();
+                        app.notifications.success(tr!("session-discarded"));
+                    }
                 });
-                
-            } // ProductsView
+            } // SettingsView
         };
 
         Ok(())
-    }();
-
-    match res {
-        Err(error) => {
-            let mut open = true;
-            egui::Window::new("Ошибка").open(&mut open).show(ctx, |ui| {
-                ui.vertical(|ui| {
-                    ui.label(error.to_string());
+    };
+
+    if let Err(error) = res() {
+        app.notifications.error(&error);
+    };
+
+    app.notifications.show(ctx);
+}
+
+/// Editors of the sales filter. Each bound applies once its checkbox is ticked.
+fn sales_filter(
+    ui: &mut egui::Ui,
+    filter: &mut SalesFilter,
+    partners: &[Partner],
+    products: &[Product],
+    product_types: &[ProductType],
+) {
+    let today = chrono::Local::now().date_naive();
+
+    egui::Grid::new("sales_filter_grid").num_columns(2).show(ui, |ui| {
+        ui.label(tr!("period"));
+        ui.horizontal(|ui| {
+            optional(ui, tr!("from"), &mut filter.from, today, |ui, date| date_picker(ui, "sales_filter_from", date));
+            optional(ui, tr!("to"), &mut filter.to, today, |ui, date| date_picker(ui, "sales_filter_to", date));
+        });
+        ui.end_row();
+
+        ui.label(tr!("partner"));
+        filter_choice(
+            ui,
+            "sales_filter_partner",
+            &mut filter.partner_id,
+            partners.iter().map(|partner| (partner.id.clone(), partner.partner_name.clone())),
+        );
+        ui.end_row();
+
+        ui.label(tr!("product"));
+        filter_choice(
+            ui,
+            "sales_filter_product",
+            &mut filter.product_id,
+            products.iter().map(|product| (product.id.clone(), product.product_name.clone())),
+        );
+        ui.end_row();
+
+        ui.label(tr!("product-type"));
+        filter_choice(
+            ui,
+            "sales_filter_product_type",
+            &mut filter.product_type,
+            product_types.iter().map(|product_type| (product_type.product_type.clone(), product_type.product_type.clone())),
+        );
+        ui.end_row();
+
+        ui.label(tr!("quantity"));
+        ui.horizontal(|ui| {
+            optional(ui, tr!("from"), &mut filter.min_quantity, 0, |ui, quantity| {
+                ui.add(egui::DragValue::new(quantity).range(0..=i64::MAX));
+            });
+            optional(ui, tr!("to"), &mut filter.max_quantity, 0, |ui, quantity| {
+                ui.add(egui::DragValue::new(quantity).range(0..=i64::MAX));
+            });
+        });
+        ui.end_row();
+    });
+
+    if ui.button(tr!("reset-filters")).clicked() {
+        *filter = SalesFilter::default();
+    }
+}
+
+/// Saves the current filter under a name and applies saved ones.
+fn sales_presets(ui: &mut egui::Ui, presets: &mut Vec<SalesPreset>, name: &mut String, filter: &mut SalesFilter) {
+    ui.horizontal(|ui| {
+        let current = presets.iter().find(|preset| preset.filter == *filter).map(|preset| preset.name.clone());
+        ComboBox::from_id_salt("sales_presets")
+            .selected_text(current.unwrap_or(tr!("no-preset")))
+            .show_ui(ui, |ui| {
+                presets.iter().for_each(|preset| {
+                    if ui.selectable_label(preset.filter == *filter, &preset.name).clicked() {
+                        *filter = preset.filter.clone();
+                        name.clone_from(&preset.name);
+                    }
                 });
             });
+
+        ui.add(egui::TextEdit::singleline(name).hint_text(tr!("preset-name")).desired_width(200.0));
+
+        let name = name.trim();
+        if ui.add_enabled(!name.is_empty(), egui::Button::new(tr!("save-preset"))).clicked() {
+            // A preset with the same name is overwritten
+            presets.retain(|preset| preset.name != name);
+            presets.push(SalesPreset { name: name.to_string(), filter: filter.clone() });
+            presets.sort_by(|a, b| a.name.cmp(&b.name));
         }
-        _ => (),
+        let exists = presets.iter().any(|preset| preset.name == name);
+        if ui.add_enabled(exists, egui::Button::new(tr!("delete-preset"))).clicked() {
+            presets.retain(|preset| preset.name != name);
+        }
+    });
+}
+
+/// A checkbox `label` with the editor of `value` after it while ticked;
+/// ticking starts from `default`, unticking clears the value.
+fn optional<T>(
+    ui: &mut egui::Ui,
+    label: String,
+    value: &mut Option<T>,
+    default: T,
+    editor: impl FnOnce(&mut egui::Ui, &mut T),
+) {
+    let mut enabled = value.is_some();
+    ui.checkbox(&mut enabled, label);
+    *value = match (enabled, value.take()) {
+        (true, current) => {
+            let mut current = current.unwrap_or(default);
+            editor(ui, &mut current);
+            Some(current)
+        }
+        (false, _) => None,
     };
 }
+
+/// One of `options` given as (value, name), or none for all of them.
+fn filter_choice(
+    ui: &mut egui::Ui,
+    id: &str,
+    selected: &mut Option<String>,
+    options: impl Iterator<Item = (String, String)>,
+) {
+    let options: Vec<(String, String)> = options.collect();
+    let selected_name = selected
+        .as_ref()
+        .and_then(|selected| options.iter().find(|(value, _)| value == selected))
+        .map(|(_, name)| name.clone())
+        .unwrap_or(tr!("all"));
+
+    ComboBox::from_id_salt(id).selected_text(selected_name).show_ui(ui, |ui| {
+        ui.selectable_value(selected, None, tr!("all"));
+        options.into_iter().for_each(|(value, name)| {
+            ui.selectable_value(selected, Some(value), name);
+        });
+    });
+}
+
+fn date_picker(ui: &mut egui::Ui, id: &str, date: &mut chrono::NaiveDate) {
+    ui.add(DatePickerButton::new(date).id_salt(id));
+}
+
+/// Chooses the language of the interface.
+fn language_switcher(ui: &mut egui::Ui, id: &str) {
+    let mut language = i18n::language();
+    ComboBox::from_id_salt(id)
+        .selected_text(language.name())
+        .show_ui(ui, |ui| {
+            Language::ALL.iter().for_each(|option| {
+                ui.selectable_value(&mut language, *option, option.name());
+            })
+        });
+    i18n::set_language(language);
+}
+
+/// Step chart of the partner's rating over time, ending at the current rating.
+fn rating_chart(ui: &mut egui::Ui, partner: &Partner, history: &[RatingChange]) {
+    if history.is_empty() {
+        ui.label(tr!("rating-unchanged"));
+        return;
+    }
+
+    let day = |date: chrono::DateTime<chrono::Utc>| date.timestamp() as f64 / 86400.0;
+
+    let mut points: Vec<[f64; 2]> = vec![];
+    history.iter().for_each(|change| {
+        points.push([day(change.changed_at), change.old_rating as f64]);
+        points.push([day(change.changed_at), change.new_rating as f64]);
+    });
+    points.push([day(chrono::Utc::now()), partner.rating as f64]);
+
+    let format_day = |value: f64| {
+        match chrono::DateTime::from_timestamp((value * 86400.0) as i64, 0) {
+            Some(date) => format_date(date.date_naive()),
+            None => "".to_string(),
+        }
+    };
+
+    Plot::new(["rating_chart_", &partner.id].concat())
+        .height(160.0)
+        .allow_scroll(false)
+        .x_axis_formatter(move |mark, _range| format_day(mark.value))
+        .label_formatter(move |_name, point| format!("{}\n{}: {}", format_day(point.x), tr!("rating"), point.y))
+        .show(ui, |plot_ui| plot_ui.line(Line::new(PlotPoints::new(points)).name(tr!("rating"))));
+
+    egui::CollapsingHeader::new(tr!("rating-history"))
+        .id_salt(["rating_history_", &partner.id].concat())
+        .show(ui, |ui| {
+            history.iter().rev().for_each(|change| {
+                ui.label(format!(
+                    "{}: {} → {}. {}",
+                    format_date(change.changed_at.date_naive()),
+                    change.old_rating,
+                    change.new_rating,
+                    change.comment
+                ));
+            });
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use demo_core::models::sales;
+    use demo_core::test_util::{any_date, connect_in_memory};
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// The column and the rule a form reports as broken.
+    fn broken<T>(result: demo_core::Result<T>) -> Option<(String, Rule)> {
+        match result {
+            Err(demo_core::Error::Validation { column, rule, .. }) => Some((column, rule)),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn sales_form_starts_today() {
+        assert_eq!(SalesForm::default().sale_date, chrono::Local::now().date_naive());
+    }
+
+    #[test]
+    fn sales_form_requires_a_product_a_partner_and_a_positive_quantity() {
+        let form = SalesForm {
+            product: Some(Product::default()),
+            quantity: 5,
+            partner: Some(Partner::default()),
+            ..Default::default()
+        };
+        assert!(form.sale().is_ok());
+
+        assert_eq!(broken(SalesForm { product: None, ..form.clone() }.sale()), Some(("product_id".into(), Rule::Required)));
+        assert_eq!(broken(SalesForm { partner: None, ..form.clone() }.sale()), Some(("partner_id".into(), Rule::Required)));
+        assert_eq!(broken(SalesForm { quantity: 0, ..form.clone() }.sale()), Some(("quantity".into(), Rule::Positive)));
+        assert_eq!(broken(SalesForm { quantity: -5, ..form }.sale()), Some(("quantity".into(), Rule::Positive)));
+    }
+
+    #[test]
+    fn products_form_requires_a_product_type() {
+        let form = ProductsForm { product_type: Some(ProductType::new("Ламинат".into(), 2.35)), ..Default::default() };
+        assert_eq!(form.product().unwrap().product_type, "Ламинат");
+        assert_eq!(broken(ProductsForm::default().product()), Some(("product_type".into(), Rule::Required)));
+    }
+
+    #[test]
+    fn materials_forms_require_what_they_refer_to() {
+        let material = MaterialsForm { supplier: Some(Supplier::default()), ..Default::default() };
+        assert!(material.material().is_ok());
+        assert_eq!(broken(MaterialsForm::default().material()), Some(("supplier_id".into(), Rule::Required)));
+
+        let entry = ProductMaterialsForm {
+            product: Some(Product::default()),
+            material: Some(Material::default()),
+            quantity: 0.5,
+        };
+        assert!(entry.product_material().is_ok());
+        assert_eq!(broken(ProductMaterialsForm { product: None, ..entry.clone() }.product_material()), Some(("product_id".into(), Rule::Required)));
+        assert_eq!(broken(ProductMaterialsForm { material: None, ..entry.clone() }.product_material()), Some(("material_id".into(), Rule::Required)));
+        assert_eq!(broken(ProductMaterialsForm { quantity: 0.0, ..entry }.product_material()), Some(("quantity".into(), Rule::Positive)));
+    }
+
+    #[test]
+    fn next_sale_goes_to_the_same_partner() {
+        let form = SalesForm {
+            product: Some(Product::default()),
+            quantity: 5,
+            sale_date: chrono::NaiveDate::from_ymd_opt(2024, 1, 10).unwrap(),
+            partner: Some(Partner::default()),
+        };
+        let next = form.next();
+
+        assert_eq!(next.partner, form.partner);
+        assert_eq!(next.product, None);
+        assert_eq!(next.quantity, 0);
+        assert_eq!(next.sale_date, chrono::Local::now().date_naive());
+    }
+
+    #[test]
+    fn sale_dates_round_trip_through_the_form_and_the_database() {
+        let db = block_on(connect_in_memory()).unwrap();
+        let partner = Partner { partner_type: "ООО".into(), ..Partner::default() };
+        block_on(partner.insert(&db)).unwrap();
+        block_on(ProductType::new("Ламинат".into(), 2.35).insert(&db)).unwrap();
+        let product = Product::new("Ламинат".into(), "Ламинат Дуб 12 мм".into(), "7750282".into(), 1799);
+        block_on(product.insert(&db)).unwrap();
+
+        proptest!(|(day in any_date())| {
+            let form = SalesForm {
+                product: Some(product.clone()),
+                quantity: 1,
+                sale_date: day,
+                partner: Some(partner.clone()),
+            };
+            let sale = form.sale().unwrap();
+            block_on(sale.insert(&db)).unwrap();
+
+            let shown = block_on(sales::get_all_detailed(&db, &[], None, 1)).unwrap();
+            prop_assert_eq!(shown[0].sale.sale_date, day);
+
+            block_on(sale.delete(&db)).unwrap();
+        });
+    }
+
+    #[test]
+    fn revealing_a_sale_clears_the_filter_hiding_it() {
+        let db = block_on(connect_in_memory()).unwrap();
+        let partner = Partner { partner_type: "ООО".into(), ..Partner::default() };
+        block_on(partner.insert(&db)).unwrap();
+        block_on(ProductType::new("Ламинат".into(), 2.35).insert(&db)).unwrap();
+        let product = Product::new("Ламинат".into(), "Ламинат Дуб 12 мм".into(), "7750282".into(), 1799);
+        block_on(product.insert(&db)).unwrap();
+        let sale = Sale::new(product.id.clone(), 1, chrono::Local::now().date_naive(), partner.id.clone());
+        block_on(sale.insert(&db)).unwrap();
+        let mut app = MyEguiApp {
+            db,
+            history: Default::default(),
+            current_view: Views::MainView,
+            view_data: Default::default(),
+            notifications: Default::default(),
+            palette: Default::default(),
+            settings: Default::default(),
+            sales_presets: vec![],
+        };
+        app.view_data.sales_views.filter.min_quantity = Some(1000);
+
+        execute(&mut app, Command::Reveal(Views::Sales, sale.id.clone()));
+        let sales_view = &mut app.view_data.sales_views;
+        refresh(&app.db, &mut sales_view.pager, sales_view.filter.filters(), &app.view_data.reveal, |details| &details.sale.id, &mut app.notifications);
+
+        assert_eq!(app.current_view, Views::Sales);
+        assert!(sales_view.pager.items().iter().any(|details| details.sale.id == sale.id));
+    }
+}
@@ -0,0 +1,365 @@
+//! Building blocks shared by the views: a scrolled list of record cards and a
+//! modal form, both described by their fields so a new entity only lists what
+//! to show and edit.
+
+use std::ops::RangeInclusive;
+
+use eframe::egui::{self, emath::Numeric, Align, Align2, Color32, ComboBox, DragValue, Id, Key, Modifiers, Response, RichText, Stroke, Ui};
+use egui_extras::DatePickerButton;
+
+use crate::i18n::tr;
+use crate::notifications::Notifications;
+
+type Text<'a, T> = Box<dyn Fn(&T) -> String + 'a>;
+type Warning<'a, T> = Box<dyn Fn(&T) -> Option<String> + 'a>;
+type Actions<'a, T> = Box<dyn FnMut(&mut Ui, &T) + 'a>;
+type Reveal<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+type LoadMore<'a> = Box<dyn FnMut() + 'a>;
+
+/// Cards of records in a scroll area. Each card shows the title and the
+/// fields; with actions the title expands into buttons.
+pub struct ListView<'a, T> {
+    id: &'a str,
+    title: Text<'a, T>,
+    fields: Vec<(String, Text<'a, T>)>,
+    warning: Option<Warning<'a, T>>,
+    deletable: bool,
+    actions: Option<Actions<'a, T>>,
+    reveal: Option<Reveal<'a, T>>,
+    load_more: Option<LoadMore<'a>>,
+    max_height: Option<f32>,
+}
+
+impl<'a, T> ListView<'a, T> {
+    pub fn new(id: &'a str, title: impl Fn(&T) -> String + 'a) -> Self {
+        ListView {
+            id,
+            title: Box::new(title),
+            fields: vec![],
+            warning: None,
+            deletable: false,
+            actions: None,
+            reveal: None,
+            load_more: None,
+            max_height: None,
+        }
+    }
+
+    pub fn field(mut self, label: impl Into<String>, value: impl Fn(&T) -> String + 'a) -> Self {
+        self.fields.push((label.into(), Box::new(value)));
+        self
+    }
+
+    /// Text shown in red under the fields when the record needs attention.
+    pub fn warning(mut self, warning: impl Fn(&T) -> Option<String> + 'a) -> Self {
+        self.warning = Some(Box::new(warning));
+        self
+    }
+
+    /// Adds a delete button to every card, Delete over a card does the same.
+    /// After the user confirms, [`show`](Self::show) returns the record to delete.
+    pub fn deletable(mut self) -> Self {
+        self.deletable = true;
+        self
+    }
+
+    /// Extra buttons and content inside the expanded title.
+    pub fn actions(mut self, actions: impl FnMut(&mut Ui, &T) + 'a) -> Self {
+        self.actions = Some(Box::new(actions));
+        self
+    }
+
+    /// Scrolls to the records matching `reveal` and expands them.
+    pub fn reveal(mut self, reveal: impl Fn(&T) -> bool + 'a) -> Self {
+        self.reveal = Some(Box::new(reveal));
+        self
+    }
+
+    /// For a list loaded page by page: while there is `more`, calls
+    /// `load_more` once the end of the list scrolls into view.
+    pub fn paged(mut self, more: bool, load_more: impl FnMut() + 'a) -> Self {
+        self.load_more = more.then(|| Box::new(load_more) as LoadMore<'a>);
+        self
+    }
+
+    pub fn max_height(mut self, max_height: f32) -> Self {
+        self.max_height = Some(max_height);
+        self
+    }
+
+}
+
+impl<'a, T: Clone + Send + Sync + 'static> ListView<'a, T> {
+    pub fn show(mut self, ui: &mut Ui, items: Vec<T>) -> Option<T> {
+        // The record waiting for confirmation lives in the egui memory between frames
+        let confirmation_id = Id::new(self.id).with("delete_confirmation");
+        let delete_key = !ui.ctx().wants_keyboard_input() && ui.input(|i| i.key_pressed(Key::Delete));
+
+        let mut scroll_area = egui::ScrollArea::vertical().id_salt(self.id);
+        if let Some(max_height) = self.max_height {
+            scroll_area = scroll_area.max_height(max_height);
+        }
+
+        scroll_area.show(ui, |ui| {
+            items.into_iter().for_each(|item| {
+                let mut delete = false;
+                let revealed = self.reveal.as_ref().is_some_and(|reveal| reveal(&item));
+
+                let card = egui::Frame::default()
+                    .stroke(Stroke::new(1.0, Color32::GRAY))
+                    .inner_margin(0.4)
+                    .outer_margin(0.8)
+                    .show(ui, |ui| {
+                        ui.set_min_size((ui.available_width() - 0.2, 0.0).into());
+
+                        let title = RichText::new((self.title)(&item)).strong().size(20.0);
+                        if self.deletable || self.actions.is_some() {
+                            egui::CollapsingHeader::new(title)
+                                .open(revealed.then_some(true))
+                                .show(ui, |ui| {
+                                    delete = self.deletable && ui.button(tr!("delete")).clicked();
+                                    if let Some(actions) = &mut self.actions {
+                                        actions(ui, &item);
+                                    }
+                                });
+                        } else {
+                            ui.label(title);
+                        }
+
+                        self.fields.iter().for_each(|(label, value)| {
+                            ui.horizontal(|ui| {
+                                ui.label(RichText::new([label, ": "].concat()).size(14.0));
+                                ui.label(value(&item));
+                            });
+                        });
+
+                        if let Some(warning) = self.warning.as_ref().and_then(|warning| warning(&item)) {
+                            ui.label(RichText::new(warning).color(Color32::RED));
+                        }
+                    })
+                    .response;
+
+                if revealed {
+                    card.scroll_to_me(Some(Align::Center));
+                }
+                if delete || (self.deletable && delete_key && card.contains_pointer()) {
+                    ui.data_mut(|data| data.insert_temp(confirmation_id, item));
+                }
+            });
+
+            if let Some(load_more) = &mut self.load_more {
+                let end = ui.vertical_centered(|ui| ui.spinner()).inner;
+                if ui.is_rect_visible(end.rect) {
+                    load_more();
+                }
+            }
+        });
+
+        let pending: Option<T> = ui.data(|data| data.get_temp(confirmation_id));
+        let (confirmed, cancelled) = match &pending {
+            Some(item) => confirm_delete(ui.ctx(), confirmation_id, &(self.title)(item)),
+            None => (false, false),
+        };
+        if confirmed || cancelled {
+            ui.data_mut(|data| data.remove::<T>(confirmation_id));
+        }
+
+        pending.filter(|_| confirmed)
+    }
+}
+
+/// Asks whether to delete the record `title`. Returns whether the user
+/// confirmed or cancelled, Enter and Escape do the same.
+fn confirm_delete(ctx: &egui::Context, id: Id, title: &str) -> (bool, bool) {
+    let (mut confirmed, mut cancelled) = ctx.input_mut(|i| {
+        (
+            i.consume_key(Modifiers::NONE, Key::Enter),
+            i.consume_key(Modifiers::NONE, Key::Escape),
+        )
+    });
+
+    egui::Window::new(tr!("confirm-delete"))
+        .id(id)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, [0.0, 0.0])
+        .show(ctx, |ui| {
+            ui.label(tr!("delete-record", name = title));
+            ui.horizontal(|ui| {
+                confirmed |= ui.button(RichText::new(tr!("delete")).color(Color32::RED)).clicked();
+                cancelled |= ui.button(tr!("cancel")).clicked();
+            });
+        });
+
+    (confirmed, cancelled)
+}
+
+/// Look of a number editor.
+#[derive(Clone)]
+struct NumberOptions {
+    speed: f64,
+    range: Option<RangeInclusive<f64>>,
+}
+
+type Editor<'a, T> = Box<dyn Fn(&mut Ui, &str, &NumberOptions, &mut T) -> Response + 'a>;
+
+/// An editor of one value of the form `T`.
+pub struct Field<'a, T> {
+    label: String,
+    options: NumberOptions,
+    editor: Editor<'a, T>,
+}
+
+impl<'a, T: 'a> Field<'a, T> {
+    fn new(label: impl Into<String>, editor: impl Fn(&mut Ui, &str, &NumberOptions, &mut T) -> Response + 'a) -> Self {
+        Field {
+            label: label.into(),
+            options: NumberOptions { speed: 1.0, range: None },
+            editor: Box::new(editor),
+        }
+    }
+
+    pub fn text(label: impl Into<String>, value: fn(&mut T) -> &mut String) -> Self {
+        Field::new(label, move |ui, _, _, form| ui.text_edit_singleline(value(form)))
+    }
+
+    pub fn multiline(label: impl Into<String>, value: fn(&mut T) -> &mut String) -> Self {
+        Field::new(label, move |ui, _, _, form| ui.text_edit_multiline(value(form)))
+    }
+
+    pub fn number<N: Numeric>(label: impl Into<String>, value: fn(&mut T) -> &mut N) -> Self {
+        Field::new(label, move |ui, _, options, form| {
+            let mut drag_value = DragValue::new(value(form)).speed(options.speed);
+            if let Some(range) = &options.range {
+                drag_value = drag_value.range(range.clone());
+            }
+            ui.add(drag_value)
+        })
+    }
+
+    pub fn date(label: impl Into<String>, value: fn(&mut T) -> &mut chrono::NaiveDate) -> Self {
+        Field::new(label, move |ui, id, _, form| {
+            ui.add(DatePickerButton::new(value(form)).id_salt(id))
+        })
+    }
+
+    /// One of `options`, shown by `name`.
+    pub fn choice<C: Clone + PartialEq + 'a>(
+        label: impl Into<String>,
+        value: fn(&mut T) -> &mut Option<C>,
+        options: Vec<C>,
+        name: fn(&C) -> String,
+    ) -> Self {
+        Field::new(label, move |ui, id, _, form| {
+            let selected = value(form);
+            ComboBox::from_id_salt(id)
+                .selected_text(selected.as_ref().map(name).unwrap_or_default())
+                .show_ui(ui, |ui| {
+                    options.iter().for_each(|option| {
+                        ui.selectable_value(selected, Some(option.clone()), name(option));
+                    })
+                })
+                .response
+        })
+    }
+
+    /// Step of a number editor per pixel of dragging.
+    pub fn speed(mut self, speed: f64) -> Self {
+        self.options.speed = speed;
+        self
+    }
+
+    /// Bounds of a number editor.
+    pub fn range(mut self, range: RangeInclusive<f64>) -> Self {
+        self.options.range = Some(range);
+        self
+    }
+}
+
+type Validator<'a, T> = Box<dyn Fn(&T) -> Option<String> + 'a>;
+
+/// A window editing a form `T` field by field, with a button to submit it.
+/// Enter in a single line field submits too.
+pub struct FormView<'a, T> {
+    title: String,
+    fields: Vec<Field<'a, T>>,
+    validator: Option<Validator<'a, T>>,
+}
+
+impl<'a, T> FormView<'a, T> {
+    pub fn new(title: impl Into<String>) -> Self {
+        FormView { title: title.into(), fields: vec![], validator: None }
+    }
+
+    pub fn field(mut self, field: Field<'a, T>) -> Self {
+        self.fields.push(field);
+        self
+    }
+
+    /// Keeps the form from being submitted while `problem` returns what is
+    /// wrong with it, shown under the disabled button.
+    pub fn validate(mut self, problem: impl Fn(&T) -> Option<String> + 'a) -> Self {
+        self.validator = Some(Box::new(problem));
+        self
+    }
+
+    /// What is wrong with the form, for windows that add their own buttons.
+    pub fn problem(&self, form: &T) -> Option<String> {
+        self.validator.as_ref().and_then(|problem| problem(form))
+    }
+
+    /// Just the labelled editors, for windows that add their own buttons.
+    /// Returns whether the user pressed Enter in one of them to submit.
+    pub fn show_fields(&self, ui: &mut Ui, form: &mut T) -> bool {
+        let mut entered = false;
+        self.fields.iter().for_each(|field| {
+            ui.label(&field.label);
+            let response = (field.editor)(ui, &[&self.title, "_", &field.label].concat(), &field.options, form);
+            entered |= response.lost_focus() && ui.input(|i| i.key_pressed(Key::Enter));
+        });
+        entered
+    }
+
+    /// Shows the window while `open`. A successful `submit` closes it, a failed
+    /// one is reported to `notifications`.
+    pub fn show(
+        &self,
+        ctx: &egui::Context,
+        open: &mut bool,
+        form: &mut T,
+        notifications: &mut Notifications,
+        submit: impl FnOnce(&T) -> demo_core::Result<()>,
+    ) {
+        let mut submitted = false;
+        egui::Window::new(&self.title).open(open).show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                let entered = self.show_fields(ui, form);
+                let problem = self.problem(form);
+
+                let button = ui.add_enabled(problem.is_none(), egui::Button::new(RichText::new(tr!("submit")).size(20.0)));
+                if let Some(problem) = &problem {
+                    ui.label(RichText::new(problem).weak());
+                }
+
+                if button.clicked() || (entered && problem.is_none()) {
+                    match submit(form) {
+                        Ok(_) => {
+                            notifications.success(tr!("record-submitted", form = self.title.as_str()));
+                            submitted = true;
+                        }
+                        Err(e) => notifications.error(&e),
+                    }
+                }
+            })
+        });
+
+        if submitted {
+            *open = false;
+        }
+    }
+}
+
+/// A large button of a view's side panel.
+pub fn panel_button(ui: &mut Ui, text: impl Into<String>) -> bool {
+    ui.button(RichText::new(text).size(20.0)).clicked()
+}
@@ -0,0 +1,264 @@
+//! JSON API over the models of `demo-core`, for integrations that cannot link
+//! the crate. [`router`] builds the whole application so tests can drive it
+//! without opening a socket.
+
+use axum::{
+    extract::{Path, Query, Request, State},
+    http::{header, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use demo_core::models::address::Address;
+use demo_core::models::partners::Partner;
+use demo_core::models::product_types::ProductType;
+use demo_core::models::products::Product;
+use demo_core::models::repository::{Filter, Pagination, Repository, Value};
+use demo_core::models::sales::{Sale, SalesFilter};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+/// Page size when the request does not give one.
+pub const DEFAULT_LIMIT: i64 = 50;
+/// Largest page a single request may ask for.
+pub const MAX_LIMIT: i64 = 500;
+
+#[derive(Clone)]
+struct AppState {
+    db: SqlitePool,
+    token: String,
+}
+
+/// The API with every route behind `Authorization: Bearer <token>`. An empty
+/// token lets no request through.
+pub fn router(db: SqlitePool, token: String) -> Router {
+    let state = AppState { db, token };
+
+    Router::new()
+        .merge(resource::<Partner>("/partners"))
+        .merge(resource::<Product>("/products"))
+        .merge(resource::<ProductType>("/product-types"))
+        .merge(resource::<Sale>("/sales"))
+        .layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state)
+}
+
+async fn authenticate(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let authorized = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| !state.token.is_empty() && same_token(token, &state.token));
+
+    if authorized {
+        next.run(request).await
+    } else {
+        ApiError::new(StatusCode::UNAUTHORIZED, "Неверный или отсутствующий токен".into())
+            .into_response()
+    }
+}
+
+/// Compares every byte whatever the first difference, so that the time of a
+/// response does not tell a client how much of a guessed token is right.
+fn same_token(given: &str, token: &str) -> bool {
+    given.len() == token.len() && given.bytes().zip(token.bytes()).fold(0, |difference, (a, b)| difference | (a ^ b)) == 0
+}
+
+#[derive(Deserialize)]
+struct PageParams {
+    limit: Option<i64>,
+    offset: Option<i64>,
+}
+
+impl PageParams {
+    fn limit(&self) -> i64 {
+        self.limit.unwrap_or(DEFAULT_LIMIT).clamp(0, MAX_LIMIT)
+    }
+
+    fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+
+    fn pagination(&self) -> Pagination {
+        Pagination { limit: self.limit(), offset: self.offset() }
+    }
+}
+
+/// One page of a list together with the size of the whole list.
+#[derive(Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// An error sent to the client as `{"error": "..."}`.
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: String) -> Self {
+        ApiError { status, message }
+    }
+}
+
+impl From<demo_core::Error> for ApiError {
+    fn from(error: demo_core::Error) -> Self {
+        let status = match &error {
+            demo_core::Error::NotFound { .. } => StatusCode::NOT_FOUND,
+            demo_core::Error::UniqueViolation { .. } => StatusCode::CONFLICT,
+            demo_core::Error::ForeignKeyViolation { .. }
+            | demo_core::Error::Validation { .. }
+            | demo_core::Error::Any(_) => StatusCode::UNPROCESSABLE_ENTITY,
+            demo_core::Error::DatabaseError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError::new(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body {
+            error: String,
+        }
+
+        (self.status, Json(Body { error: self.message })).into_response()
+    }
+}
+
+/// A model served under its own path: `GET`/`POST` on the list,
+/// `GET`/`PUT`/`DELETE` on `/{key}`.
+trait Resource: Repository + Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Query parameters narrowing the list.
+    type Query: DeserializeOwned + Send + 'static;
+
+    fn filters(query: Self::Query) -> Vec<Filter>;
+
+    /// Completes a record received from a client before it is written.
+    fn prepare(&mut self) -> demo_core::Result<()> {
+        Ok(())
+    }
+}
+
+#[derive(Deserialize)]
+struct NoQuery {}
+
+fn ensure_id(id: &mut String) {
+    if id.is_empty() {
+        *id = Uuid::new_v4().to_string();
+    }
+}
+
+impl Resource for Partner {
+    type Query = NoQuery;
+
+    fn filters(_: NoQuery) -> Vec<Filter> {
+        vec![]
+    }
+
+    fn prepare(&mut self) -> demo_core::Result<()> {
+        ensure_id(&mut self.id);
+        self.set_address(Address::parse(&self.legal_address)?);
+        Ok(())
+    }
+}
+
+impl Resource for Product {
+    type Query = NoQuery;
+
+    fn filters(_: NoQuery) -> Vec<Filter> {
+        vec![]
+    }
+
+    fn prepare(&mut self) -> demo_core::Result<()> {
+        ensure_id(&mut self.id);
+        Ok(())
+    }
+}
+
+impl Resource for ProductType {
+    type Query = NoQuery;
+
+    fn filters(_: NoQuery) -> Vec<Filter> {
+        vec![]
+    }
+}
+
+impl Resource for Sale {
+    type Query = SalesFilter;
+
+    fn filters(query: SalesFilter) -> Vec<Filter> {
+        query.filters()
+    }
+
+    fn prepare(&mut self) -> demo_core::Result<()> {
+        ensure_id(&mut self.id);
+        self.validate()
+    }
+}
+
+fn resource<T: Resource>(path: &str) -> Router<AppState> {
+    Router::new()
+        .route(path, get(list::<T>).post(create::<T>))
+        .route(
+            &format!("{}/{{key}}", path),
+            get(fetch::<T>).put(update::<T>).delete(delete::<T>),
+        )
+}
+
+async fn list<T: Resource>(
+    State(state): State<AppState>,
+    Query(page): Query<PageParams>,
+    Query(query): Query<T::Query>,
+) -> ApiResult<Json<Page<T>>> {
+    let filters = T::filters(query);
+    Ok(Json(Page {
+        items: T::list(&state.db, &filters, Some(page.pagination())).await?,
+        total: T::count(&state.db, &filters).await?,
+        limit: page.limit(),
+        offset: page.offset(),
+    }))
+}
+
+async fn fetch<T: Resource>(State(state): State<AppState>, Path(key): Path<String>) -> ApiResult<Json<T>> {
+    Ok(Json(T::fetch(&state.db, key).await?))
+}
+
+async fn create<T: Resource>(
+    State(state): State<AppState>,
+    Json(mut record): Json<T>,
+) -> ApiResult<(StatusCode, Json<T>)> {
+    record.prepare()?;
+    record.insert(&state.db).await?;
+    Ok((StatusCode::CREATED, Json(record)))
+}
+
+async fn update<T: Resource>(
+    State(state): State<AppState>,
+    Path(key): Path<String>,
+    Json(mut record): Json<T>,
+) -> ApiResult<Json<T>> {
+    if record.key() != Value::from(key) {
+        return Err(ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Ключ записи не совпадает с адресом".into(),
+        ));
+    }
+    record.prepare()?;
+    record.update(&state.db).await?;
+    Ok(Json(T::fetch(&state.db, record.key()).await?))
+}
+
+async fn delete<T: Resource>(State(state): State<AppState>, Path(key): Path<String>) -> ApiResult<StatusCode> {
+    T::fetch(&state.db, key).await?.delete(&state.db).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,49 @@
+use std::net::SocketAddr;
+
+use clap::Parser;
+
+/// Serves the application's data as JSON for other programs.
+#[derive(Parser)]
+#[command(name = "demo-server", version)]
+struct Cli {
+    /// Database to serve, the same one the desktop application opens.
+    #[arg(long, env = "DATABASE_URL", default_value = demo_core::DEFAULT_DATABASE_URL)]
+    database_url: String,
+
+    /// Address to listen on. Keep it on localhost unless a proxy handles TLS.
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    listen: SocketAddr,
+
+    /// Token clients send as `Authorization: Bearer <token>`.
+    #[arg(long, env = "DEMO_API_TOKEN", value_parser = parse_token)]
+    token: String,
+}
+
+/// An empty token would be one any client can send.
+fn parse_token(token: &str) -> Result<String, String> {
+    if token.trim().is_empty() {
+        return Err("the token must not be empty".into());
+    }
+    Ok(token.to_string())
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+
+    if let Err(e) = run(cli).await {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(cli: Cli) -> demo_core::Result<()> {
+    let db = demo_core::connect(&cli.database_url).await?;
+    let listener = tokio::net::TcpListener::bind(cli.listen)
+        .await
+        .map_err(|e| demo_core::Error::Any(e.to_string()))?;
+
+    axum::serve(listener, demo_server::router(db, cli.token))
+        .await
+        .map_err(|e| demo_core::Error::Any(e.to_string()))
+}
@@ -0,0 +1,382 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+const TOKEN: &str = "secret";
+
+/// The API over a fresh in-memory database.
+async fn app() -> Router {
+    let db = demo_core::test_util::connect_in_memory().await.unwrap();
+
+    demo_server::router(db, TOKEN.into())
+}
+
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(header::AUTHORIZATION, format!("Bearer {}", TOKEN))
+        .header(header::CONTENT_TYPE, "application/json");
+    let request = match body {
+        Some(body) => request.body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    }
+    .unwrap();
+
+    let response = app.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let body = if bytes.is_empty() { Value::Null } else { serde_json::from_slice(&bytes).unwrap() };
+
+    (status, body)
+}
+
+fn partner(name: &str, inn: &str) -> Value {
+    json!({
+        "partner_type": "ООО",
+        "partner_name": name,
+        "director": "Иванов Иван Иванович",
+        "email": "info@example.com",
+        "phone": "+7 900 000 00 00",
+        "legal_address": "123456, Московская область, город Москва, ул. Ленина, 1",
+        "inn": inn,
+        "rating": 5
+    })
+}
+
+/// Creates a product type, a product and two partners, returning the ids of
+/// the product and the partners.
+async fn seed(app: &Router) -> (String, String, String) {
+    let (status, _) = send(app, Method::POST, "/product-types", Some(json!({
+        "product_type": "Ламинат",
+        "coefficient": 2.35
+    }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, product) = send(app, Method::POST, "/products", Some(json!({
+        "product_type": "Ламинат",
+        "product_name": "Ламинат дубовый",
+        "article_number": "8758385",
+        "minimum_cost": 4456
+    }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (_, first) = send(app, Method::POST, "/partners", Some(partner("База Строитель", "2222455179"))).await;
+    let (_, second) = send(app, Method::POST, "/partners", Some(partner("Паркет 29", "3333888520"))).await;
+
+    (
+        product["id"].as_str().unwrap().into(),
+        first["id"].as_str().unwrap().into(),
+        second["id"].as_str().unwrap().into(),
+    )
+}
+
+async fn sell(app: &Router, product: &str, partner: &str, date: &str) {
+    let (status, _) = send(app, Method::POST, "/sales", Some(json!({
+        "product_id": product,
+        "partner_id": partner,
+        "quantity": 100,
+        "sale_date": date
+    }))).await;
+    assert_eq!(status, StatusCode::CREATED);
+}
+
+#[tokio::test]
+async fn rejects_requests_without_the_token() {
+    let app = app().await;
+
+    let response = app
+        .clone()
+        .oneshot(Request::get("/partners").body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    let response = app
+        .oneshot(
+            Request::get("/partners")
+                .header(header::AUTHORIZATION, "Bearer wrong")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn rejects_tokens_differing_in_one_character() {
+    let response = app()
+        .await
+        .oneshot(
+            Request::get("/partners")
+                .header(header::AUTHORIZATION, "Bearer secreT")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn an_empty_token_lets_nothing_through() {
+    let db = demo_core::test_util::connect_in_memory().await.unwrap();
+
+    let response = demo_server::router(db, String::new())
+        .oneshot(
+            Request::get("/partners")
+                .header(header::AUTHORIZATION, "Bearer ")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn partner_crud() {
+    let app = app().await;
+
+    let (status, created) = send(&app, Method::POST, "/partners", Some(partner("База Строитель", "2222455179"))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = created["id"].as_str().unwrap();
+    assert!(!id.is_empty());
+    assert_eq!(created["city"], "Москва");
+    assert_eq!(created["postal_index"], "123456");
+
+    let (status, fetched) = send(&app, Method::GET, &format!("/partners/{}", id), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched, created);
+
+    let (status, _) = send(&app, Method::DELETE, &format!("/partners/{}", id), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (status, body) = send(&app, Method::GET, &format!("/partners/{}", id), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], format!("Партнер: запись «{}» не найдена", id));
+}
+
+#[tokio::test]
+async fn partner_update_splits_the_new_address_and_keeps_the_rating() {
+    let app = app().await;
+
+    let (_, mut body) = send(&app, Method::POST, "/partners", Some(partner("База Строитель", "2222455179"))).await;
+    let id = body["id"].as_str().unwrap().to_string();
+    body["legal_address"] = json!("141400, Московская область, г. Химки, ул. Молодежная, 2");
+    body["rating"] = json!(10);
+    let (status, updated) = send(&app, Method::PUT, &format!("/partners/{}", id), Some(body)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["city"], "Химки");
+    assert_eq!(updated["postal_index"], "141400");
+    assert_eq!(updated["rating"], 5);
+
+    let (_, fetched) = send(&app, Method::GET, &format!("/partners/{}", id), None).await;
+    assert_eq!(fetched, updated);
+}
+
+#[tokio::test]
+async fn rejects_partner_with_unparsable_address() {
+    let app = app().await;
+
+    let mut body = partner("База Строитель", "2222455179");
+    body["legal_address"] = json!("Москва");
+    let (status, body) = send(&app, Method::POST, "/partners", Some(body)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].is_string());
+}
+
+#[tokio::test]
+async fn paginates_partners() {
+    let app = app().await;
+    for i in 0..5 {
+        send(&app, Method::POST, "/partners", Some(partner(&format!("Партнер {}", i), &i.to_string()))).await;
+    }
+
+    let (status, page) = send(&app, Method::GET, "/partners?limit=2&offset=3", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 5);
+    assert_eq!(page["limit"], 2);
+    assert_eq!(page["offset"], 3);
+    let names: Vec<_> = page["items"].as_array().unwrap().iter().map(|p| p["partner_name"].clone()).collect();
+    assert_eq!(names, [json!("Партнер 3"), json!("Партнер 4")]);
+}
+
+#[tokio::test]
+async fn product_and_product_type_crud() {
+    let app = app().await;
+    let (product, _, _) = seed(&app).await;
+
+    let (status, fetched) = send(&app, Method::GET, &format!("/products/{}", product), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched["article_number"], "8758385");
+
+    let (status, body) = send(&app, Method::POST, "/product-types", Some(json!({
+        "product_type": "Ламинат",
+        "coefficient": 1.0
+    }))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(
+        body["error"],
+        "Тип продукта: запись с таким значением поля «Тип продукта» уже существует"
+    );
+
+    let (status, page) = send(&app, Method::GET, "/product-types", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(page["total"], 1);
+
+    let (status, _) = send(&app, Method::DELETE, &format!("/products/{}", product), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, Method::DELETE, "/product-types/%D0%9B%D0%B0%D0%BC%D0%B8%D0%BD%D0%B0%D1%82", None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, page) = send(&app, Method::GET, "/products", None).await;
+    assert_eq!(page["total"], 0);
+}
+
+#[tokio::test]
+async fn filters_sales_by_partner_and_date() {
+    let app = app().await;
+    let (product, first, second) = seed(&app).await;
+    sell(&app, &product, &first, "2024-01-15").await;
+    sell(&app, &product, &first, "2024-02-15").await;
+    sell(&app, &product, &first, "2024-03-15").await;
+    sell(&app, &product, &second, "2024-02-20").await;
+
+    let (_, page) = send(&app, Method::GET, &format!("/sales?partner_id={}", first), None).await;
+    assert_eq!(page["total"], 3);
+
+    let (_, page) = send(&app, Method::GET, "/sales?from=2024-02-01&to=2024-02-29", None).await;
+    assert_eq!(page["total"], 2);
+
+    let (_, page) = send(
+        &app,
+        Method::GET,
+        &format!("/sales?partner_id={}&from=2024-02-15&limit=1", first),
+        None,
+    )
+    .await;
+    assert_eq!(page["total"], 2);
+    let items = page["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["sale_date"], "2024-03-15");
+}
+
+#[tokio::test]
+async fn filters_sales_by_product_type_and_quantity() {
+    let app = app().await;
+    let (laminate, partner, _) = seed(&app).await;
+    send(&app, Method::POST, "/product-types", Some(json!({
+        "product_type": "Паркетная доска",
+        "coefficient": 4.34
+    }))).await;
+    let (_, parquet) = send(&app, Method::POST, "/products", Some(json!({
+        "product_type": "Паркетная доска",
+        "product_name": "Паркетная доска Ясень",
+        "article_number": "7750282",
+        "minimum_cost": 1799
+    }))).await;
+    let parquet = parquet["id"].as_str().unwrap();
+
+    for (product, quantity) in [(laminate.as_str(), 50), (laminate.as_str(), 150), (parquet, 300)] {
+        let (status, _) = send(&app, Method::POST, "/sales", Some(json!({
+            "product_id": product,
+            "partner_id": partner,
+            "quantity": quantity,
+            "sale_date": "2024-01-01"
+        }))).await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let (_, page) = send(&app, Method::GET, "/sales?product_type=Ламинат", None).await;
+    assert_eq!(page["total"], 2);
+
+    let (_, page) = send(&app, Method::GET, &format!("/sales?product_id={}", parquet), None).await;
+    assert_eq!(page["total"], 1);
+
+    let (_, page) = send(&app, Method::GET, "/sales?min_quantity=100&max_quantity=200", None).await;
+    assert_eq!(page["total"], 1);
+    assert_eq!(page["items"][0]["quantity"], 150);
+}
+
+#[tokio::test]
+async fn sale_requires_existing_partner_and_product() {
+    let app = app().await;
+    let (product, _, _) = seed(&app).await;
+
+    let (status, body) = send(&app, Method::POST, "/sales", Some(json!({
+        "product_id": product,
+        "partner_id": "missing",
+        "quantity": 1,
+        "sale_date": "2024-01-01"
+    }))).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["error"], "Продажа: в поле «Партнер» указана несуществующая запись");
+}
+
+#[tokio::test]
+async fn sale_quantity_must_be_positive() {
+    let app = app().await;
+    let (product, partner, _) = seed(&app).await;
+    let sale = |quantity: i64| json!({
+        "product_id": product,
+        "partner_id": partner,
+        "quantity": quantity,
+        "sale_date": "2024-01-01"
+    });
+
+    for quantity in [0, -5] {
+        let (status, body) = send(&app, Method::POST, "/sales", Some(sale(quantity))).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"], "Продажа, поле «Количество»: должно быть больше нуля");
+    }
+
+    let (status, created) = send(&app, Method::POST, "/sales", Some(sale(1))).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let mut body = created.clone();
+    body["quantity"] = json!(0);
+    let (status, _) = send(&app, Method::PUT, &format!("/sales/{}", created["id"].as_str().unwrap()), Some(body)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (_, page) = send(&app, Method::GET, "/sales", None).await;
+    assert_eq!(page["items"][0]["quantity"], 1);
+}
+
+#[tokio::test]
+async fn deleting_a_partner_deletes_its_sales() {
+    let app = app().await;
+    let (product, first, _) = seed(&app).await;
+    sell(&app, &product, &first, "2024-01-15").await;
+
+    let (status, _) = send(&app, Method::DELETE, &format!("/partners/{}", first), None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+
+    let (_, page) = send(&app, Method::GET, "/sales", None).await;
+    assert_eq!(page["total"], 0);
+}
+
+#[tokio::test]
+async fn updates_a_product() {
+    let app = app().await;
+    let (product, _, _) = seed(&app).await;
+
+    let (_, mut body) = send(&app, Method::GET, &format!("/products/{}", product), None).await;
+    body["minimum_cost"] = json!(5000);
+    let (status, _) = send(&app, Method::PUT, &format!("/products/{}", product), Some(body.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, fetched) = send(&app, Method::GET, &format!("/products/{}", product), None).await;
+    assert_eq!(fetched["minimum_cost"], 5000);
+
+    let (status, _) = send(&app, Method::PUT, "/products/other", Some(body.clone())).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    body["id"] = json!("missing");
+    let (status, _) = send(&app, Method::PUT, "/products/missing", Some(body)).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}